@@ -33,61 +33,298 @@ fn extract_tarball(src: &Path, dst: &Path) -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
-fn download_and_extract(url: &str, dst: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    // Ensure destination parent exists
+/// Compute the lowercase hex SHA-256 of `data`.
+///
+/// Vendored here so the build stays dependency-light; tarball integrity is the
+/// only thing we hash, so a compact reference implementation is enough.
+fn sha256_hex(data: &[u8]) -> String {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let bit_len: u64 = (data.len() as u64).wrapping_mul(8);
+    let mut msg: Vec<u8> = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&bit_len.to_be_bytes());
+
+    let mut w: [u32; 64] = [0; 64];
+    for block in msg.chunks_exact(64) {
+        for (i, word) in w.iter_mut().take(16).enumerate() {
+            *word = u32::from_be_bytes([
+                block[i * 4],
+                block[i * 4 + 1],
+                block[i * 4 + 2],
+                block[i * 4 + 3],
+            ]);
+        }
+        for i in 16..64 {
+            let s0: u32 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1: u32 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let mut v: [u32; 8] = h;
+        for i in 0..64 {
+            let s1: u32 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+            let ch: u32 = (v[4] & v[5]) ^ ((!v[4]) & v[6]);
+            let t1: u32 = v[7]
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0: u32 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+            let maj: u32 = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+            let t2: u32 = s0.wrapping_add(maj);
+            v[7] = v[6];
+            v[6] = v[5];
+            v[5] = v[4];
+            v[4] = v[3].wrapping_add(t1);
+            v[3] = v[2];
+            v[2] = v[1];
+            v[1] = v[0];
+            v[0] = t1.wrapping_add(t2);
+        }
+        for (dst, src) in h.iter_mut().zip(v.iter()) {
+            *dst = dst.wrapping_add(*src);
+        }
+    }
+
+    let mut out: String = String::with_capacity(64);
+    for word in h {
+        out.push_str(&format!("{word:08x}"));
+    }
+    out
+}
+
+/// The ordered list of base URLs to try for a release asset.
+///
+/// `PACM_V8_PREBUILT_MIRRORS` (comma-separated) is consulted first so offline or
+/// corporate users can point at an internal store or a `file://` path; the
+/// canonical GitHub release base is appended as the final fallback unless the
+/// mirror list already names it, in which case it keeps its listed position.
+fn mirror_bases(default_base: &str) -> Vec<String> {
+    let mut bases: Vec<String> = Vec::new();
+    if let Ok(list) = env::var("PACM_V8_PREBUILT_MIRRORS") {
+        for entry in list.split(',') {
+            let trimmed: &str = entry.trim().trim_end_matches('/');
+            if !trimmed.is_empty() {
+                bases.push(trimmed.to_string());
+            }
+        }
+    }
+    let default_trimmed: &str = default_base.trim_end_matches('/');
+    if !bases.iter().any(|b| b == default_trimmed) {
+        bases.push(default_trimmed.to_string());
+    }
+    bases
+}
+
+/// Whether the V8 build should be the debug profile.
+///
+/// `V8_DEBUG=1`/`=0` is an explicit override; otherwise cargo's own `PROFILE`
+/// (always set for build scripts) decides, so a plain `cargo build --release`
+/// pulls the release prebuilt instead of a debug one.
+fn v8_debug_requested() -> bool {
+    match env::var("V8_DEBUG").ok().as_deref() {
+        Some("1") => return true,
+        Some("0") => return false,
+        _ => {}
+    }
+    env::var("PROFILE").map(|p| p == "debug").unwrap_or(false)
+}
+
+/// The profile component of the cache key: `"debug"` or `"release"`.
+fn profile_tag(is_debug: bool) -> &'static str {
+    if is_debug {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+/// The shared, content-addressed cache directory for a `(tag, triple, profile)`
+/// triple.
+///
+/// `PACM_V8_CACHE_DIR` overrides the location; otherwise it lives under
+/// `$CARGO_HOME/pacm-v8/` (falling back to `~/.cargo`). Populated once and
+/// copied into each `OUT_DIR`, so `cargo clean` and sibling workspaces reuse it.
+fn prebuilt_cache_dir(tag: &str, triple: &str, profile: &str) -> Option<PathBuf> {
+    if let Ok(dir) = env::var("PACM_V8_CACHE_DIR") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir).join(tag).join(triple).join(profile));
+        }
+    }
+    if let Ok(home) = env::var("CARGO_HOME") {
+        if !home.is_empty() {
+            return Some(
+                PathBuf::from(home)
+                    .join("pacm-v8")
+                    .join(tag)
+                    .join(triple)
+                    .join(profile),
+            );
+        }
+    }
+    env::var("HOME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .map(|home| {
+            PathBuf::from(home)
+                .join(".cargo")
+                .join("pacm-v8")
+                .join(tag)
+                .join(triple)
+                .join(profile)
+        })
+}
+
+/// Ensure the prebuilt for `(tag, triple, profile)` is present, using the
+/// shared cache, and materialise it into `v8_dst`.
+///
+/// The cache is populated on first use (download + verify + extract); subsequent
+/// builds copy from it instead of re-downloading hundreds of megabytes.
+fn prepare_prebuilt(
+    bases: &[String],
+    filename: &str,
+    tag: &str,
+    triple: &str,
+    profile: &str,
+    v8_dst: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match prebuilt_cache_dir(tag, triple, profile) {
+        Some(cache) => {
+            if !cache.join("include").exists() {
+                println!("Populating shared cache at {}", cache.display());
+                download_and_extract(bases, filename, &cache)?;
+            } else {
+                println!("Reusing cached v8 prebuilt at {}", cache.display());
+            }
+            if v8_dst.exists() {
+                fs::remove_dir_all(v8_dst)?;
+            }
+            copy_dir_all(&cache, v8_dst)?;
+            Ok(())
+        }
+        None => {
+            // No cache location resolvable; download straight into OUT_DIR.
+            download_and_extract(bases, filename, v8_dst)
+        }
+    }
+}
+
+/// Fetch the bytes at `url`. Local `file://` URLs are read directly; everything
+/// else goes through the HTTP client.
+fn fetch_bytes(client: &Client, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return Ok(fs::read(path)?);
+    }
+    let mut req = client.get(url).header("Accept", "application/octet-stream");
+    if let Ok(token) = env::var("GITHUB_TOKEN") {
+        if !token.is_empty() {
+            req = req.bearer_auth(token);
+        }
+    }
+    let resp = req.send()?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP status {}", resp.status()).into());
+    }
+    Ok(resp.bytes()?.to_vec())
+}
+
+/// Resolve the expected SHA-256 for `filename` from its sibling
+/// `{filename}.sha256` asset on `base`, if one is published.
+fn resolve_expected_sha(client: &Client, base: &str, filename: &str) -> Option<String> {
+    let url: String = format!("{base}/{filename}.sha256");
+    let bytes: Vec<u8> = fetch_bytes(client, &url).ok()?;
+    let text: String = String::from_utf8_lossy(&bytes).into_owned();
+    // `sha256sum` format is "<hex>  <name>"; take the first whitespace token.
+    text.split_whitespace()
+        .next()
+        .filter(|tok| tok.len() == 64 && tok.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|tok| tok.to_ascii_lowercase())
+}
+
+/// Download `filename` from the first working mirror, verify its SHA-256 when an
+/// expected digest is published, and extract it into `dst`.
+fn download_and_extract(
+    bases: &[String],
+    filename: &str,
+    dst: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
     if let Some(parent) = dst.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    // Prepare HTTP client with long timeouts and proper UA
-    let builder = Client::builder()
+    let client = Client::builder()
         .user_agent("pacm-v8-build/1.0")
         .timeout(Duration::from_secs(600))
-        .connect_timeout(Duration::from_secs(60));
+        .connect_timeout(Duration::from_secs(60))
+        .build()?;
 
-    let client = builder.build()?;
-
-    // Download with a few retries, streaming to disk
     let tmp: PathBuf = dst.with_extension("download");
     let attempts: usize = 3;
     let mut last_err: Option<String> = None;
-    for attempt in 1..=attempts {
-        let mut req = client.get(url).header("Accept", "application/octet-stream");
-        if let Ok(token) = env::var("GITHUB_TOKEN") {
-            if !token.is_empty() {
-                req = req.bearer_auth(token);
-            }
-        }
 
-        match req.send() {
-            Ok(mut resp) => {
-                if !resp.status().is_success() {
-                    last_err = Some(format!("HTTP status {}", resp.status()));
-                } else {
-                    let mut file = File::create(&tmp)?;
-                    std::io::copy(&mut resp, &mut file)?;
-                    // Try to extract and return
+    for base in bases {
+        let url: String = format!("{base}/{filename}");
+        let expected: Option<String> = resolve_expected_sha(&client, base, filename);
+
+        for attempt in 1..=attempts {
+            match fetch_bytes(&client, &url) {
+                Ok(bytes) => {
+                    if let Some(expected) = &expected {
+                        let actual: String = sha256_hex(&bytes);
+                        if &actual != expected {
+                            // A corrupted or tampered asset must never be extracted.
+                            return Err(format!(
+                                "SHA-256 mismatch for {url}: expected {expected}, got {actual}"
+                            )
+                            .into());
+                        }
+                    } else {
+                        println!(
+                            "cargo:warning=No {filename}.sha256 published on {base}; skipping integrity check"
+                        );
+                    }
+                    fs::write(&tmp, &bytes)?;
                     extract_tarball(&tmp, dst)?;
-                    let _ = std::fs::remove_file(&tmp);
+                    let _ = fs::remove_file(&tmp);
                     return Ok(());
                 }
+                Err(err) => last_err = Some(format!("{url}: {err}")),
             }
-            Err(err) => {
-                last_err = Some(err.to_string());
-            }
-        }
 
-        if attempt < attempts {
-            let backoff = 2_u64.pow(attempt as u32);
-            println!(
-                "cargo:warning=Download attempt {attempt} failed; retrying in {backoff}s"
-            );
-            std::thread::sleep(Duration::from_secs(backoff));
+            if attempt < attempts {
+                let backoff: u64 = 2_u64.pow(attempt as u32);
+                println!("cargo:warning=Download attempt {attempt} failed; retrying in {backoff}s");
+                std::thread::sleep(Duration::from_secs(backoff));
+            }
         }
     }
 
     Err(format!(
-        "Download failed after {attempts} attempts: {}",
+        "Download failed across {} mirror(s): {}",
+        bases.len(),
         last_err.unwrap_or_else(|| "unknown error".into())
     )
     .into())
@@ -206,14 +443,61 @@ fn find_fallback_librarian(manifest_dir: &Path, os: &str) -> Option<PathBuf> {
     None
 }
 
-fn find_librarian(manifest_dir: &Path) -> Option<PathBuf> {
+/// A tool capable of assembling object files into a static archive.
+///
+/// The two variants speak incompatible command-line dialects, which is why
+/// [`create_static_library_from_objects`] branches on this instead of
+/// shelling out the same way to both.
+enum Librarian {
+    /// `lib.exe` / `llvm-lib`: takes an `@response` file of MSVC-style
+    /// `/OUT:` and bare object-path arguments.
+    Msvc(PathBuf),
+    /// GNU `ar` (or `llvm-ar`): supports `@response` files too, and a `T`
+    /// (thin archive) mode that references objects in place instead of
+    /// copying them into the archive — the only way to keep a V8-sized
+    /// object set from blowing past OS command-length limits without also
+    /// doubling disk usage.
+    Ar(PathBuf),
+}
+
+/// Locate an `ar`-compatible archiver, preferred on non-Windows targets
+/// because thin archives avoid copying V8's thousands of `.o` files.
+fn find_ar(manifest_dir: &Path) -> Option<PathBuf> {
+    if let Some(path) = find_program_on_path(&["llvm-ar", "ar"]) {
+        return Some(path);
+    }
+
+    let os: &str = if cfg!(target_os = "macos") { "macos" } else { "linux" };
+    let fallback: PathBuf = manifest_dir
+        .join("third_party")
+        .join("v8")
+        .join("buildtools")
+        .join(os)
+        .join("llvm-build")
+        .join("Release+Asserts")
+        .join("bin")
+        .join("llvm-ar");
+    if fallback.exists() {
+        return Some(fallback);
+    }
+
+    None
+}
+
+fn find_librarian(manifest_dir: &Path) -> Option<Librarian> {
+    if cfg!(not(target_os = "windows")) {
+        if let Some(path) = find_ar(manifest_dir) {
+            return Some(Librarian::Ar(path));
+        }
+    }
+
     let win_candidates: [&str; 4] = ["lib.exe", "LLVM-LIB.EXE", "llvm-lib.exe", "LLVM-LIB.EXE"];
     let unix_candidates: [&str; 3] = ["lib", "LLVM-LIB", "llvm-lib"];
 
     if cfg!(target_os = "windows") {
         for candidate in &win_candidates {
             if let Some(path) = find_program_on_path(&[*candidate]) {
-                return Some(path);
+                return Some(Librarian::Msvc(path));
             }
         }
     }
@@ -221,7 +505,7 @@ fn find_librarian(manifest_dir: &Path) -> Option<PathBuf> {
     if cfg!(not(target_os = "windows")) {
         for candidate in &unix_candidates {
             if let Some(path) = find_program_on_path(&[*candidate]) {
-                return Some(path);
+                return Some(Librarian::Msvc(path));
             }
         }
     }
@@ -235,7 +519,7 @@ fn find_librarian(manifest_dir: &Path) -> Option<PathBuf> {
     };
 
     if let Some(path) = find_fallback_librarian(manifest_dir, os) {
-        return Some(path);
+        return Some(Librarian::Msvc(path));
     }
 
     None
@@ -384,6 +668,15 @@ fn should_regenerate(target: &Path, inputs: &[PathBuf]) -> bool {
     }
 }
 
+/// Bundle every object file directly under `obj_dir` into a single static
+/// archive, returning the archive path so the caller can push it into
+/// `link_search_dirs`/`extra_libs` unchanged.
+///
+/// V8 emits thousands of objects per target, so objects are never copied
+/// onto one giant command line: the MSVC path writes them into an
+/// `@response` file for `lib.exe`/`llvm-lib`, and the GNU path builds a thin
+/// archive (`ar ... T`) that references the objects in place rather than
+/// copying their contents in, which is both faster and far smaller on disk.
 fn create_static_library_from_objects(
     obj_dir: &Path,
     out_dir: &Path,
@@ -400,7 +693,7 @@ fn create_static_library_from_objects(
         if path
             .extension()
             .and_then(|ext: &OsStr| ext.to_str())
-            .map(|ext: &str| ext.eq_ignore_ascii_case("obj"))
+            .map(|ext: &str| ext.eq_ignore_ascii_case("obj") || ext.eq_ignore_ascii_case("o"))
             .unwrap_or(false)
         {
             objects.push(path);
@@ -411,26 +704,44 @@ fn create_static_library_from_objects(
         return None;
     }
 
-    let lib_path: PathBuf = out_dir.join(format!("{lib_basename}.lib"));
-    if !should_regenerate(&lib_path, &objects) {
-        return Some(lib_path);
-    }
-
-    let librarian: PathBuf = match find_librarian(manifest_dir) {
-        Some(p) => p,
+    let librarian: Librarian = match find_librarian(manifest_dir) {
+        Some(l) => l,
         None => {
             println!(
-                "cargo:warning=Could not find lib/llvm-lib; skipping custom libc++ bundling"
+                "cargo:warning=Could not find ar/lib/llvm-lib; skipping custom libc++ bundling"
             );
             return None;
         }
     };
 
+    match librarian {
+        Librarian::Ar(ar) => {
+            create_thin_archive(&ar, out_dir, lib_basename, &objects)
+        }
+        Librarian::Msvc(lib_exe) => {
+            create_msvc_archive(&lib_exe, out_dir, lib_basename, &objects)
+        }
+    }
+}
+
+/// Assemble `objects` into `{lib_basename}.lib` by invoking `lib.exe`/
+/// `llvm-lib` against an `@response` file of MSVC-style arguments.
+fn create_msvc_archive(
+    lib_exe: &Path,
+    out_dir: &Path,
+    lib_basename: &str,
+    objects: &[PathBuf],
+) -> Option<PathBuf> {
+    let lib_path: PathBuf = out_dir.join(format!("{lib_basename}.lib"));
+    if !should_regenerate(&lib_path, objects) {
+        return Some(lib_path);
+    }
+
     let rsp_path: PathBuf = out_dir.join(format!("{lib_basename}.rsp"));
     let mut rsp_content: String = String::new();
     rsp_content.push_str("/nologo\n");
     rsp_content.push_str(&format!("/OUT:\"{}\"\n", lib_path.display()));
-    for obj in &objects {
+    for obj in objects {
         rsp_content.push_str(&format!("\"{}\"\n", obj.display()));
     }
 
@@ -442,13 +753,13 @@ fn create_static_library_from_objects(
         );
     }
 
-    let status: ExitStatus = Command::new(&librarian)
+    let status: ExitStatus = Command::new(lib_exe)
         .arg(format!("@{}", rsp_path.display()))
         .status()
         .unwrap_or_else(|err| {
             panic!(
                 "Failed to invoke {} to bundle libc++ objects: {}",
-                librarian.display(),
+                lib_exe.display(),
                 err
             );
         });
@@ -456,7 +767,7 @@ fn create_static_library_from_objects(
     if !status.success() {
         panic!(
             "{} failed while creating {}; see output above for details",
-            librarian.display(),
+            lib_exe.display(),
             lib_path.display()
         );
     }
@@ -466,6 +777,863 @@ fn create_static_library_from_objects(
     Some(lib_path)
 }
 
+/// Assemble `objects` into `{lib_basename}.a` as a GNU `ar` thin archive
+/// (`T` modifier): the archive stores references to the objects' paths
+/// rather than copies of their contents, so creation is O(1) in object size
+/// and the resulting `.a` stays tiny even for tens of thousands of objects.
+///
+/// The object list is passed via an `@response` file rather than on the
+/// command line for the same reason the MSVC path uses one: V8's object
+/// counts alone can exceed `ARG_MAX`/`CreateProcess`'s command-length limit.
+fn create_thin_archive(
+    ar: &Path,
+    out_dir: &Path,
+    lib_basename: &str,
+    objects: &[PathBuf],
+) -> Option<PathBuf> {
+    let lib_path: PathBuf = out_dir.join(format!("{lib_basename}.a"));
+    if !should_regenerate(&lib_path, objects) {
+        return Some(lib_path);
+    }
+
+    let rsp_path: PathBuf = out_dir.join(format!("{lib_basename}.rsp"));
+    let mut rsp_content: String = String::new();
+    for obj in objects {
+        rsp_content.push_str(&format!("{}\n", obj.display()));
+    }
+
+    if let Err(err) = fs::write(&rsp_path, rsp_content) {
+        panic!(
+            "Failed to write response file for libc++ bundling at {}: {}",
+            rsp_path.display(),
+            err
+        );
+    }
+
+    let _ = fs::remove_file(&lib_path);
+
+    let status: ExitStatus = Command::new(ar)
+        .arg("qcsT")
+        .arg(&lib_path)
+        .arg(format!("@{}", rsp_path.display()))
+        .status()
+        .unwrap_or_else(|err| {
+            panic!(
+                "Failed to invoke {} to bundle libc++ objects: {}",
+                ar.display(),
+                err
+            );
+        });
+
+    if !status.success() {
+        panic!(
+            "{} failed while creating {}; see output above for details",
+            ar.display(),
+            lib_path.display()
+        );
+    }
+
+    let _ = fs::remove_file(&rsp_path);
+
+    Some(lib_path)
+}
+
+/// How the V8 monolith should be linked, modelled on rustc's stackable
+/// crate-type / `prefer-dynamic` policy.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LinkPolicy {
+    /// Link the static archive (the default when it is present).
+    Static,
+    /// Link the shared library.
+    Dynamic,
+    /// Link the shared library when available, otherwise fall back to static.
+    PreferDynamic,
+    /// Register both library directories; link dynamically and keep the static
+    /// path available for consumers that need it.
+    Both,
+    /// No explicit request: link whichever single artifact is present.
+    Auto,
+}
+
+/// Parse `V8_LINK_KIND`. Unknown or unset values fall back to [`LinkPolicy::Auto`].
+fn parse_link_policy() -> LinkPolicy {
+    match env::var("V8_LINK_KIND").ok().as_deref() {
+        Some("static") => LinkPolicy::Static,
+        Some("dylib") | Some("dynamic") => LinkPolicy::Dynamic,
+        Some("prefer-dynamic") => LinkPolicy::PreferDynamic,
+        Some("both") => LinkPolicy::Both,
+        _ => LinkPolicy::Auto,
+    }
+}
+
+/// Recursively copy `src` into `dst`, creating directories as needed. Used to
+/// stage a freshly built V8 checkout into the same layout a prebuilt uses.
+fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry: DirEntry = entry?;
+        let from: PathBuf = entry.path();
+        let to: PathBuf = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&from, &to)?;
+        } else {
+            fs::copy(&from, &to)?;
+        }
+    }
+    Ok(())
+}
+
+/// Map a Rust target triple onto the GN `target_cpu` value V8 expects.
+fn gn_target_cpu(target_triple: &str) -> &'static str {
+    if target_triple.starts_with("x86_64") || target_triple.starts_with("x86_64h") {
+        "x64"
+    } else if target_triple.starts_with("aarch64") || target_triple.starts_with("arm64") {
+        "arm64"
+    } else if target_triple.starts_with("armv7") || target_triple.starts_with("arm") {
+        "arm"
+    } else if target_triple.starts_with("i686") || target_triple.starts_with("i586") {
+        "x86"
+    } else if target_triple.starts_with("riscv64") {
+        "riscv64"
+    } else {
+        "x64"
+    }
+}
+
+/// Render an `args.gn` for a monolithic, statically linked V8 build keyed on the
+/// target triple. The pointer-compression, sandbox, and custom-libc++ toggles
+/// that [`load_v8_build_config`] otherwise only *reads* are set here so a
+/// from-source build matches what the crate links against; each honours an
+/// environment override.
+fn generate_args_gn(target_triple: &str) -> String {
+    let env_flag = |name: &str, default: bool| -> bool {
+        match env::var(name).ok().as_deref() {
+            Some("1") => true,
+            Some("0") => false,
+            _ => default,
+        }
+    };
+
+    let pointer_compression: bool = env_flag("V8_ENABLE_POINTER_COMPRESSION", true);
+    let sandbox: bool = env_flag("V8_ENABLE_SANDBOX", pointer_compression);
+    let custom_libcxx: bool = env_flag("V8_USE_CUSTOM_LIBCXX", false);
+    let is_debug: bool = v8_debug_requested();
+
+    format!(
+        "target_cpu = \"{cpu}\"\n\
+         is_debug = {debug}\n\
+         is_component_build = false\n\
+         v8_monolithic = true\n\
+         v8_use_external_startup_data = false\n\
+         use_custom_libcxx = {custom_libcxx}\n\
+         v8_enable_pointer_compression = {ptr}\n\
+         v8_enable_sandbox = {sandbox}\n\
+         treat_warnings_as_errors = false\n",
+        cpu = gn_target_cpu(target_triple),
+        debug = is_debug,
+        custom_libcxx = custom_libcxx,
+        ptr = pointer_compression,
+        sandbox = sandbox,
+    )
+}
+
+/// Build V8 from a local `third_party/v8` checkout with GN + ninja and stage the
+/// result into a prebuilt-shaped directory (`include/` + `lib/`).
+///
+/// Returns `None` (with a warning) when the checkout or the `gn`/`ninja` tools
+/// are missing, so the caller can fall back to its normal "unsupported" error.
+fn build_v8_from_source(
+    manifest_dir: &Path,
+    out_dir: &Path,
+    target_triple: &str,
+) -> Option<PathBuf> {
+    let checkout: PathBuf = manifest_dir.join("third_party").join("v8");
+    if !checkout.exists() {
+        println!(
+            "cargo:warning=No third_party/v8 checkout at {}; cannot build V8 from source",
+            checkout.display()
+        );
+        return None;
+    }
+
+    let gn: PathBuf = find_program_on_path(&["gn", "gn.exe"]).or_else(|| {
+        println!("cargo:warning=Could not find `gn` on PATH; cannot build V8 from source");
+        None
+    })?;
+    let ninja: PathBuf = find_program_on_path(&["ninja", "ninja.exe"]).or_else(|| {
+        println!("cargo:warning=Could not find `ninja` on PATH; cannot build V8 from source");
+        None
+    })?;
+
+    let out_subdir: PathBuf = PathBuf::from("out.gn").join(target_triple);
+    let gen_dir: PathBuf = checkout.join(&out_subdir);
+    fs::create_dir_all(&gen_dir).expect("failed to create GN output directory");
+    fs::write(gen_dir.join("args.gn"), generate_args_gn(target_triple))
+        .expect("failed to write args.gn");
+
+    println!("Generating V8 build with gn in {}", gen_dir.display());
+    let gn_status: ExitStatus = Command::new(&gn)
+        .current_dir(&checkout)
+        .arg("gen")
+        .arg(&out_subdir)
+        .status()
+        .expect("failed to invoke gn");
+    if !gn_status.success() {
+        panic!("gn gen failed for {}", out_subdir.display());
+    }
+
+    println!("Building v8_monolith with ninja");
+    let ninja_status: ExitStatus = Command::new(&ninja)
+        .arg("-C")
+        .arg(&gen_dir)
+        .arg("v8_monolith")
+        .status()
+        .expect("failed to invoke ninja");
+    if !ninja_status.success() {
+        panic!("ninja v8_monolith failed in {}", gen_dir.display());
+    }
+
+    let monolith: PathBuf = [
+        gen_dir.join("obj").join("libv8_monolith.a"),
+        gen_dir.join("libv8_monolith.a"),
+    ]
+    .into_iter()
+    .find(|p: &PathBuf| p.exists())
+    .unwrap_or_else(|| {
+        panic!(
+            "v8_monolith build completed but no libv8_monolith.a was found under {}",
+            gen_dir.display()
+        )
+    });
+
+    // Stage into the same shape a downloaded prebuilt uses so the existing
+    // include/lib resolution and the out.gn object scan can take over.
+    let stage: PathBuf = out_dir.join(format!("v8-from-source-{target_triple}"));
+    let stage_lib: PathBuf = stage.join("lib");
+    fs::create_dir_all(&stage_lib).expect("failed to create staging lib dir");
+    copy_dir_all(&checkout.join("include"), &stage.join("include"))
+        .expect("failed to stage V8 headers");
+    fs::copy(&monolith, stage_lib.join("libv8_monolith.a")).expect("failed to stage v8_monolith");
+
+    if let Some(config) = find_v8_build_config_path(&gen_dir, manifest_dir) {
+        let _ = fs::copy(&config, stage_lib.join("v8_build_config.json"));
+    }
+
+    println!("cargo:warning=Built V8 from source at {}", stage.display());
+    Some(stage)
+}
+
+/// Parse the effective relocation model from the rustc flags cargo exposes.
+///
+/// Returns `true` when the build explicitly selects a non-PIC model
+/// (`-C relocation-model=static`), in which case the shim must match so the
+/// objects link together.
+fn relocation_model_is_static() -> bool {
+    let mut flags: Vec<String> = Vec::new();
+    if let Ok(encoded) = env::var("CARGO_ENCODED_RUSTFLAGS") {
+        flags.extend(encoded.split('\u{1f}').map(str::to_string));
+    } else if let Ok(raw) = env::var("RUSTFLAGS") {
+        flags.extend(raw.split_whitespace().map(str::to_string));
+    }
+
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value: Option<&str> = if let Some(rest) = flag.strip_prefix("relocation-model=") {
+            Some(rest)
+        } else if flag == "-C" {
+            iter.next()
+                .and_then(|next| next.strip_prefix("relocation-model="))
+        } else {
+            flag.strip_prefix("-Crelocation-model=")
+        };
+        if let Some(model) = value {
+            return matches!(model, "static" | "ropi" | "rwpi" | "ropi-rwpi");
+        }
+    }
+    false
+}
+
+/// Decide whether to compile the shim as position-independent code.
+///
+/// `PACM_V8_PIC` is the explicit override (`1` forces on, `0` forces off).
+/// Otherwise PIC is enabled for every non-Windows target — mandatory for 32-bit
+/// `cdylib`/PIE links and harmless on 64-bit — unless a static relocation model
+/// was requested.
+fn wants_pic(is_windows: bool) -> bool {
+    match env::var("PACM_V8_PIC").ok().as_deref() {
+        Some("1") => return true,
+        Some("0") => return false,
+        _ => {}
+    }
+    if is_windows {
+        return false;
+    }
+    !relocation_model_is_static()
+}
+
+/// Derive architecture baseline flags from the V8 build config so the shim is
+/// compiled against the same CPU feature set as the prebuilt libraries.
+///
+/// V8's `v8_build_config.json` records the target CPU it was built for; we map
+/// that onto `-mfpu=neon`/`-msse2`/`/arch:SSE2` (SSE on x86, NEON on 32-bit arm). x86-64 already
+/// guarantees an SSE2 baseline, so no flag is emitted there.
+fn cpu_baseline_flags(config: &serde_json::Value, cargo_target: &str, is_msvc: bool) -> Vec<String> {
+    let cpu: &str = config
+        .get("v8_target_cpu")
+        .or_else(|| config.get("current_cpu"))
+        .or_else(|| config.get("target_cpu"))
+        .and_then(|v: &serde_json::Value| v.as_str())
+        .unwrap_or("");
+
+    let is_arm32: bool = cpu == "arm" || cargo_target.starts_with("armv7");
+    let is_x86_32: bool = cpu == "x86" || cargo_target.starts_with("i686");
+
+    if is_arm32 && !is_msvc {
+        // V8 on 32-bit arm assumes an Armv7-A NEON baseline.
+        return vec!["-mfpu=neon".to_string(), "-mfloat-abi=hard".to_string()];
+    }
+
+    if is_x86_32 {
+        return if is_msvc {
+            vec!["/arch:SSE2".to_string()]
+        } else {
+            vec!["-msse2".to_string(), "-mfpmath=sse".to_string()]
+        };
+    }
+
+    Vec::new()
+}
+
+/// Which C++ runtime the shim and V8 monolith link against.
+///
+/// Mirrors how libstd gates its `backtrace` linkage: `bundled-libcxx` assembles
+/// V8's own `buildtools/third_party/libc++` objects (already collected from
+/// `obj_dir` the same way `v8_libplatform_*` is) into a static archive and
+/// links that instead of the platform's `c++`/`stdc++`; `system-cxx` is the
+/// default everywhere except Windows, which has always linked its bundled
+/// libc++ automatically when the prebuilt shipped it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CxxLinkMode {
+    System,
+    Bundled,
+}
+
+/// Resolve the active [`CxxLinkMode`] plus whether it was explicitly requested
+/// (via a Cargo feature or `V8_LIBCXX`) rather than assumed from the historical
+/// per-platform default. Explicit requests fail loudly when the bundled
+/// objects are missing; the implicit Windows default stays best-effort.
+fn cxx_link_mode(target_os: &str) -> (CxxLinkMode, bool) {
+    match env::var("V8_LIBCXX").ok().as_deref() {
+        Some("bundled") => return (CxxLinkMode::Bundled, true),
+        Some("system") => return (CxxLinkMode::System, true),
+        _ => {}
+    }
+    if env::var_os("CARGO_FEATURE_BUNDLED_LIBCXX").is_some() {
+        return (CxxLinkMode::Bundled, true);
+    }
+    if env::var_os("CARGO_FEATURE_SYSTEM_CXX").is_some() {
+        return (CxxLinkMode::System, true);
+    }
+    if target_os == "windows" {
+        (CxxLinkMode::Bundled, false)
+    } else {
+        (CxxLinkMode::System, false)
+    }
+}
+
+/// Whether the opt-in C-ABI install artifacts should be emitted.
+///
+/// Enabled by `V8_EMIT_CABI=1` (or any non-empty, non-`0` value) or by the
+/// `capi` Cargo feature, mirroring cargo-c's `capi` toggle.
+fn cabi_requested() -> bool {
+    if env::var_os("CARGO_FEATURE_CAPI").is_some() {
+        return true;
+    }
+    match env::var("V8_EMIT_CABI") {
+        Ok(value) => !value.is_empty() && value != "0",
+        Err(_) => false,
+    }
+}
+
+/// Inputs needed to assemble the self-contained C-ABI artifacts. These mirror
+/// the link requirements `main` already derived for the Rust target so C/C++
+/// consumers get the same prebuilt V8 without re-deriving them.
+struct CabiInputs<'a> {
+    out_dir: &'a Path,
+    crate_version: &'a str,
+    include_path: &'a Path,
+    link_search_dirs: &'a [PathBuf],
+    monolith: &'a str,
+    extra_libs: &'a [(String, &'static str)],
+    is_windows: bool,
+    is_macos: bool,
+    system_link: &'a SystemLink,
+    cxx_mode: CxxLinkMode,
+}
+
+/// The system libraries and (on Apple platforms) frameworks a target needs
+/// linked in alongside the V8 monolith.
+///
+/// `cxx_runtime` is split out from `libs` because it is the one entry
+/// [`CxxLinkMode::Bundled`] replaces with an in-tree static archive instead of
+/// linking from the system. Names are owned rather than `&'static str` because
+/// [`resolve_system_link`] can populate this from V8's own build metadata
+/// instead of the hardcoded table.
+struct SystemLink {
+    libs: Vec<(String, &'static str)>,
+    frameworks: Vec<String>,
+    cxx_runtime: Option<String>,
+}
+
+/// Target-triple-driven table of system link requirements, covering the BSD
+/// family, Solaris/illumos and Android in addition to the Windows/macOS/Linux
+/// trio this crate has always shipped prebuilts for.
+///
+/// Driven by `CARGO_CFG_TARGET_OS`/`CARGO_CFG_TARGET_ENV` (always set for
+/// build scripts, cross-compiles included) rather than pattern-matching
+/// `TARGET`, so e.g. building for `aarch64-unknown-freebsd` from a Linux host
+/// still links the right libraries. This is the fallback [`resolve_system_link`]
+/// uses when no V8 build metadata is available to query instead.
+fn system_link(target_os: &str, target_env: &str) -> SystemLink {
+    let dylib = |names: &[&str]| -> Vec<(String, &'static str)> {
+        names.iter().map(|name| (name.to_string(), "dylib")).collect()
+    };
+    let s = |name: &str| -> Option<String> { Some(name.to_string()) };
+    match target_os {
+        "windows" => SystemLink {
+            libs: dylib(&[
+                "dbghelp", "winmm", "ws2_32", "user32", "advapi32", "ole32", "oleaut32", "shell32",
+            ]),
+            frameworks: Vec::new(),
+            cxx_runtime: None,
+        },
+        "macos" => SystemLink {
+            libs: dylib(&["m", "pthread"]),
+            frameworks: vec!["CoreFoundation".to_string(), "CoreServices".to_string()],
+            cxx_runtime: s("c++"),
+        },
+        "linux" => {
+            let mut libs: Vec<(String, &'static str)> = dylib(&["m", "pthread", "dl"]);
+            if target_env != "musl" {
+                libs.push(("rt".to_string(), "dylib"));
+            }
+            SystemLink { libs, frameworks: Vec::new(), cxx_runtime: s("stdc++") }
+        }
+        "android" => SystemLink {
+            // Android's libc has no separate `librt`; `log` replaces
+            // syslog and `c++_shared` stands in for `stdc++`/`libc++`.
+            libs: dylib(&["dl", "log"]),
+            frameworks: Vec::new(),
+            cxx_runtime: s("c++_shared"),
+        },
+        "freebsd" | "dragonfly" => SystemLink {
+            // Clang-based libc++, plus `libexecinfo` for the backtrace support
+            // glibc bundles for free on Linux.
+            libs: dylib(&["execinfo", "pthread"]),
+            frameworks: Vec::new(),
+            cxx_runtime: s("c++"),
+        },
+        "netbsd" | "openbsd" => SystemLink {
+            // NetBSD/OpenBSD link libc++ against `libgcc_s` for unwinding
+            // rather than compiler-rt.
+            libs: dylib(&["pthread", "gcc_s"]),
+            frameworks: Vec::new(),
+            cxx_runtime: s("c++"),
+        },
+        "solaris" | "illumos" => SystemLink {
+            libs: dylib(&["socket", "nsl", "pthread"]),
+            frameworks: Vec::new(),
+            cxx_runtime: s("stdc++"),
+        },
+        _ => SystemLink { libs: Vec::new(), frameworks: Vec::new(), cxx_runtime: None },
+    }
+}
+
+/// Locate an optional `v8_link.json` sidecar, checked the same places
+/// [`find_v8_build_config_path`] looks for `v8_build_config.json`.
+fn find_v8_link_sidecar_path(v8_root: &Path, manifest_dir: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = vec![
+        v8_root.join("v8_link.json"),
+        v8_root.join("lib").join("v8_link.json"),
+    ];
+
+    if let Ok(entries) = fs::read_dir(v8_root) {
+        for entry in entries.flatten() {
+            let path: PathBuf = entry.path();
+            if path.is_dir() {
+                candidates.push(path.join("v8_link.json"));
+            }
+        }
+    }
+
+    for candidate in &candidates {
+        if candidate.exists() {
+            return Some(candidate.clone());
+        }
+    }
+
+    let local_out: PathBuf = manifest_dir.join("third_party").join("v8").join("out.gn");
+    if let Ok(entries) = fs::read_dir(&local_out) {
+        for entry in entries.flatten() {
+            let candidate: PathBuf = entry.path().join("v8_link.json");
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+/// Read a `v8_link.json` sidecar: `{"libs": [{"name": .., "kind": "dylib"|"static"}], "frameworks": [..], "cxx_runtime": ..}`.
+///
+/// A GN build can emit this alongside `v8_build_config.json` to record the
+/// exact system libraries it linked against, so this crate doesn't need to
+/// guess from a hardcoded per-OS table.
+fn read_v8_link_sidecar(v8_root: &Path, manifest_dir: &Path) -> Option<SystemLink> {
+    let path: PathBuf = find_v8_link_sidecar_path(v8_root, manifest_dir)?;
+    let content: String = fs::read_to_string(&path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    let libs: Vec<(String, &'static str)> = value
+        .get("libs")?
+        .as_array()?
+        .iter()
+        .filter_map(|entry: &serde_json::Value| {
+            let name: String = entry.get("name")?.as_str()?.to_string();
+            let kind: &'static str = match entry.get("kind").and_then(|k| k.as_str()) {
+                Some("static") => "static",
+                _ => "dylib",
+            };
+            Some((name, kind))
+        })
+        .collect();
+
+    let frameworks: Vec<String> = value
+        .get("frameworks")
+        .and_then(|v: &serde_json::Value| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v: &serde_json::Value| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let cxx_runtime: Option<String> = value
+        .get("cxx_runtime")
+        .and_then(|v: &serde_json::Value| v.as_str())
+        .map(str::to_string);
+
+    println!("cargo:rerun-if-changed={}", path.display());
+    Some(SystemLink { libs, frameworks, cxx_runtime })
+}
+
+/// Scan a `toolchain.ninja`'s `v8_monolith` build edge for `-l`/`-framework`
+/// tokens, the way `rustc_llvm`'s build script shells out to
+/// `llvm-config --system-libs` instead of hardcoding what LLVM links against.
+///
+/// Best-effort: returns `None` if the file is missing or the edge carries no
+/// recognizable link flags, letting the caller fall back further.
+fn parse_toolchain_ninja(path: &Path) -> Option<SystemLink> {
+    let content: String = fs::read_to_string(path).ok()?;
+
+    let mut libs: Vec<(String, &'static str)> = Vec::new();
+    let mut frameworks: Vec<String> = Vec::new();
+    let mut cxx_runtime: Option<String> = None;
+
+    for line in content.lines() {
+        if !line.contains("v8_monolith") {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        while let Some(token) = tokens.next() {
+            if let Some(name) = token.strip_prefix("-l") {
+                if name.is_empty() {
+                    continue;
+                }
+                if matches!(name, "c++" | "stdc++" | "c++_shared") {
+                    cxx_runtime.get_or_insert_with(|| name.to_string());
+                } else if !libs.iter().any(|(n, _): &(String, &str)| n == name) {
+                    libs.push((name.to_string(), "dylib"));
+                }
+            } else if token == "-framework" {
+                if let Some(next) = tokens.next() {
+                    if !frameworks.iter().any(|f| f == next) {
+                        frameworks.push(next.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    if libs.is_empty() && frameworks.is_empty() && cxx_runtime.is_none() {
+        return None;
+    }
+    Some(SystemLink { libs, frameworks, cxx_runtime })
+}
+
+/// Scan every local `out.gn/*/toolchain.ninja` for the first one that yields
+/// link metadata via [`parse_toolchain_ninja`].
+fn parse_ninja_system_libs(manifest_dir: &Path) -> Option<SystemLink> {
+    let local_out: PathBuf = manifest_dir.join("third_party").join("v8").join("out.gn");
+    let entries = fs::read_dir(&local_out).ok()?;
+
+    for entry in entries.flatten() {
+        let toolchain_ninja: PathBuf = entry.path().join("toolchain.ninja");
+        if let Some(link) = parse_toolchain_ninja(&toolchain_ninja) {
+            println!("cargo:rerun-if-changed={}", toolchain_ninja.display());
+            return Some(link);
+        }
+    }
+    None
+}
+
+/// Resolve the system/framework libraries V8 was actually built against,
+/// rather than assuming the hardcoded [`system_link`] table stays correct as
+/// V8 changes its dependency set across versions.
+///
+/// Checked in order: a `v8_link.json` sidecar, then `toolchain.ninja`, then
+/// (since prebuilt tarballs ship neither) the static per-`target_os` table.
+fn resolve_system_link(
+    v8_root: &Path,
+    manifest_dir: &Path,
+    target_os: &str,
+    target_env: &str,
+) -> SystemLink {
+    if let Some(link) = read_v8_link_sidecar(v8_root, manifest_dir) {
+        println!("cargo:warning=Resolved system libraries from v8_link.json");
+        return link;
+    }
+    if let Some(link) = parse_ninja_system_libs(manifest_dir) {
+        println!("cargo:warning=Resolved system libraries from toolchain.ninja");
+        return link;
+    }
+    system_link(target_os, target_env)
+}
+
+/// System libraries the platform appends unconditionally. Kept in sync with the
+/// `cargo:rustc-link-lib` block at the end of `main` so the `.pc` resolves the
+/// same transitive dependencies. The C++ runtime is omitted when
+/// `cxx_mode` is [`CxxLinkMode::Bundled`]; the in-tree archive is already
+/// folded into `extra_libs`/`Libs.private` via the monolith scan.
+fn cabi_system_libs(inputs: &CabiInputs, cxx_mode: CxxLinkMode) -> Vec<String> {
+    let mut libs: Vec<String> = inputs
+        .system_link
+        .libs
+        .iter()
+        .map(|(name, _kind)| format!("-l{name}"))
+        .collect();
+    if cxx_mode == CxxLinkMode::System {
+        if let Some(cxx) = &inputs.system_link.cxx_runtime {
+            libs.push(format!("-l{cxx}"));
+        }
+    }
+    for framework in &inputs.system_link.frameworks {
+        libs.push(format!("-framework {framework}"));
+    }
+    libs
+}
+
+/// Write the `pacm-v8.pc` pkg-config file. The combined `libpacm_v8` goes in
+/// `Libs:`; the monolith, libplatform, ICU, and system libraries land in
+/// `Libs.private:` so static linkers resolve them, matching how cargo-c splits
+/// public and private requirements.
+fn write_pkg_config(inputs: &CabiInputs, lib_out: &Path, include_out: &Path) {
+    let mut private: Vec<String> = inputs
+        .link_search_dirs
+        .iter()
+        .map(|dir| format!("-L{}", dir.display()))
+        .collect();
+    private.push(format!("-l{}", inputs.monolith));
+    for (name, _kind) in inputs.extra_libs {
+        private.push(format!("-l{name}"));
+    }
+    private.extend(cabi_system_libs(inputs, inputs.cxx_mode));
+
+    let contents: String = format!(
+        "prefix={prefix}\n\
+         libdir={libdir}\n\
+         includedir={includedir}\n\
+         \n\
+         Name: pacm-v8\n\
+         Description: Self-contained V8 embedding ABI used by the pacm-v8 crate\n\
+         Version: {version}\n\
+         Cflags: -I${{includedir}}\n\
+         Libs: -L${{libdir}} -lpacm_v8\n\
+         Libs.private: {private}\n",
+        prefix = inputs.out_dir.join("cabi").display(),
+        libdir = lib_out.display(),
+        includedir = include_out.display(),
+        version = inputs.crate_version,
+        private = private.join(" "),
+    );
+
+    let pkgconfig_dir: PathBuf = lib_out.join("pkgconfig");
+    fs::create_dir_all(&pkgconfig_dir).expect("failed to create pkgconfig dir");
+    let pc_path: PathBuf = pkgconfig_dir.join("pacm-v8.pc");
+    fs::write(&pc_path, contents).expect("failed to write pacm-v8.pc");
+    println!("cargo:warning=Wrote pkg-config file {}", pc_path.display());
+}
+
+/// Emit a C header declaring the shim's exported functions so non-Rust
+/// consumers can call the same entry points the crate links against.
+fn write_cabi_header(include_out: &Path) {
+    const HEADER: &str = include_str!("src/cpp/shim.h");
+    let header_path: PathBuf = include_out.join("pacm_v8.h");
+    fs::write(&header_path, HEADER).expect("failed to write pacm_v8.h");
+    println!("cargo:warning=Wrote C header {}", header_path.display());
+}
+
+/// Link the compiled shim objects together with the resolved V8 libraries into
+/// a single self-contained shared library plus a companion static archive.
+///
+/// Best-effort: if the toolchain needed to relink (a C++ driver / archiver)
+/// cannot be found the step warns rather than failing the whole build, since
+/// the Rust link already succeeded and only the extra C-ABI bundle is affected.
+fn link_cabi_libraries(inputs: &CabiInputs, lib_out: &Path) {
+    // `cc` already archived the shim translation units into `libshim.a`
+    // (`shim.lib` on MSVC) inside OUT_DIR during `build.compile("shim")`.
+    let shim_archive: PathBuf = if inputs.is_windows {
+        inputs.out_dir.join("shim.lib")
+    } else {
+        inputs.out_dir.join("libshim.a")
+    };
+    if !shim_archive.exists() {
+        println!(
+            "cargo:warning=Skipping C-ABI libraries: {} not found",
+            shim_archive.display()
+        );
+        return;
+    }
+
+    if inputs.is_windows {
+        println!(
+            "cargo:warning=C-ABI library bundling on MSVC is not automated; link {} with the libraries listed in pacm-v8.pc",
+            shim_archive.display()
+        );
+        return;
+    }
+
+    let shared_name: String = if inputs.is_macos {
+        "libpacm_v8.dylib".to_string()
+    } else {
+        "libpacm_v8.so".to_string()
+    };
+    let shared_path: PathBuf = lib_out.join(shared_name);
+
+    let mut compiler = cc::Build::new();
+    compiler.cpp(true);
+    let tool = compiler.get_compiler();
+
+    let mut cmd = Command::new(tool.path());
+    cmd.args(tool.args());
+    cmd.arg("-shared").arg("-o").arg(&shared_path);
+    // Pull every shim object into the shared library, not just referenced ones.
+    if inputs.is_macos {
+        cmd.arg("-Wl,-force_load").arg(&shim_archive);
+    } else {
+        cmd.arg("-Wl,--whole-archive")
+            .arg(&shim_archive)
+            .arg("-Wl,--no-whole-archive");
+    }
+    for dir in inputs.link_search_dirs {
+        cmd.arg(format!("-L{}", dir.display()));
+    }
+    cmd.arg(format!("-l{}", inputs.monolith));
+    for (name, _kind) in inputs.extra_libs {
+        cmd.arg(format!("-l{name}"));
+    }
+    for flag in cabi_system_libs(inputs, inputs.cxx_mode) {
+        for token in flag.split_whitespace() {
+            cmd.arg(token);
+        }
+    }
+
+    match cmd.status() {
+        Ok(status) if status.success() => {
+            println!("cargo:warning=Wrote shared library {}", shared_path.display());
+        }
+        Ok(status) => {
+            println!(
+                "cargo:warning=Failed to link {} (exit {status}); C header and pkg-config were still written",
+                shared_path.display()
+            );
+        }
+        Err(err) => {
+            println!(
+                "cargo:warning=Could not invoke {} to link C-ABI shared library: {err}",
+                tool.path().display()
+            );
+        }
+    }
+
+    // Companion static archive: merge the shim objects with the monolith so a
+    // fully static consumer can link `-lpacm_v8` alone against Libs.private.
+    let static_path: PathBuf = lib_out.join("libpacm_v8.a");
+    let monolith_archive: Option<PathBuf> = inputs
+        .link_search_dirs
+        .iter()
+        .map(|dir| dir.join(format!("lib{}.a", inputs.monolith)))
+        .find(|p| p.exists());
+
+    let ar: PathBuf =
+        find_program_on_path(&["llvm-ar", "ar"]).unwrap_or_else(|| PathBuf::from("ar"));
+    let mut script: String = String::from("create ");
+    script.push_str(&static_path.to_string_lossy());
+    script.push('\n');
+    script.push_str(&format!("addlib {}\n", shim_archive.display()));
+    if let Some(monolith) = &monolith_archive {
+        script.push_str(&format!("addlib {}\n", monolith.display()));
+    }
+    script.push_str("save\nend\n");
+
+    match Command::new(&ar)
+        .arg("-M")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+    {
+        Ok(mut child) => {
+            use std::io::Write;
+            if let Some(stdin) = child.stdin.as_mut() {
+                let _ = stdin.write_all(script.as_bytes());
+            }
+            match child.wait() {
+                Ok(status) if status.success() => {
+                    println!(
+                        "cargo:warning=Wrote static library {}",
+                        static_path.display()
+                    );
+                }
+                _ => println!(
+                    "cargo:warning=Failed to assemble {}; consumers can still link the shared library",
+                    static_path.display()
+                ),
+            }
+        }
+        Err(err) => println!(
+            "cargo:warning=Could not invoke {} to build static C-ABI archive: {err}",
+            ar.display()
+        ),
+    }
+}
+
+/// Assemble the cinstall-style C-ABI artifacts (header, pkg-config, combined
+/// libraries) under `OUT_DIR/cabi` and record the directory for consumers.
+fn emit_cabi_artifacts(inputs: &CabiInputs) {
+    let cabi_dir: PathBuf = inputs.out_dir.join("cabi");
+    let include_out: PathBuf = cabi_dir.join("include");
+    let lib_out: PathBuf = cabi_dir.join("lib");
+    fs::create_dir_all(&include_out).expect("failed to create C-ABI include dir");
+    fs::create_dir_all(&lib_out).expect("failed to create C-ABI lib dir");
+
+    write_cabi_header(&include_out);
+    link_cabi_libraries(inputs, &lib_out);
+    write_pkg_config(inputs, &lib_out, &include_out);
+
+    println!("cargo:rustc-env=PACM_V8_CABI_DIR={}", cabi_dir.display());
+}
+
 fn main() {
     for file in [
         "shim.h",
@@ -477,6 +1645,26 @@ fn main() {
     ] {
         println!("cargo:rerun-if-changed=src/cpp/{file}");
     }
+    for var in [
+        "V8_PREBUILT_TARGET",
+        "V8_TARGET_TRIPLE",
+        "V8_PREBUILT_REPO",
+        "V8_FROM_SOURCE",
+        "V8_LINK_KIND",
+        "V8_DEBUG",
+        "V8_ENABLE_POINTER_COMPRESSION",
+        "V8_ENABLE_SANDBOX",
+        "V8_USE_CUSTOM_LIBCXX",
+        "PACM_V8_PREBUILT",
+        "PACM_V8_PREBUILT_MIRRORS",
+        "PACM_V8_CACHE_DIR",
+        "PACM_V8_PIC",
+        "PROFILE",
+        "CXXFLAGS",
+        "V8_LIBCXX",
+    ] {
+        println!("cargo:rerun-if-env-changed={var}");
+    }
 
     let out_dir: PathBuf = PathBuf::from(env::var("OUT_DIR").unwrap());
     let cargo_target: String = env::var("TARGET").unwrap();
@@ -493,25 +1681,51 @@ fn main() {
     let effective_tag: String = format!("v8-{crate_version}");
     println!("cargo:rustc-env=PACM_V8_PREBUILT_TAG={effective_tag}");
 
-    // Asset name convention
-    let filename: String = format!("v8-{target_triple}.tar.gz");
-    let download_url: String =
-        format!("https://github.com/{repo}/releases/download/{effective_tag}/{filename}");
+    // Asset name convention: profile-qualified so a debug build never pulls a
+    // release prebuilt (or vice versa) out of the shared cache.
+    let is_debug: bool = v8_debug_requested();
+    let profile: &str = profile_tag(is_debug);
+    let filename: String = if is_debug {
+        format!("v8-{target_triple}-debug.tar.gz")
+    } else {
+        format!("v8-{target_triple}.tar.gz")
+    };
+    let default_base: String =
+        format!("https://github.com/{repo}/releases/download/{effective_tag}");
+    let bases: Vec<String> = mirror_bases(&default_base);
 
     // Always download (per crate version), isolate cache path by tag to avoid cross-version reuse
     let v8_dst: PathBuf = out_dir.join(format!("v8-prebuilt-{}-{}", target_triple, effective_tag));
-    if v8_dst.exists() && v8_dst.join("include").exists() {
+    let from_source: bool = matches!(env::var("V8_FROM_SOURCE").ok().as_deref(), Some("1"));
+    // Force a re-resolution through the content-addressed cache (still a cheap
+    // local copy on a cache hit) instead of blindly reusing whatever is already
+    // staged in OUT_DIR, e.g. after bumping a mirror or rotating a compromised asset.
+    let force_prebuilt_refresh: bool = env::var_os("PACM_V8_PREBUILT").is_some();
+
+    let v8_root: PathBuf = if from_source {
+        // Explicit opt-in: skip the download entirely and build from the checkout.
+        build_v8_from_source(&manifest_dir, &out_dir, &target_triple)
+            .expect("V8_FROM_SOURCE=1 but the source build failed; see warnings above")
+    } else if !force_prebuilt_refresh && v8_dst.exists() && v8_dst.join("include").exists() {
         println!("Found existing v8 prebuilt at {}", v8_dst.display());
+        resolve_prebuilt_root(&v8_dst)
     } else {
-        println!(
-            "Downloading v8 prebuilt from: {}",
-            download_url
-        );
-        download_and_extract(&download_url, &v8_dst)
-            .expect("Failed to download or extract v8 prebuilt. Please check if your system and architecture are supported.");
-    }
-
-    let v8_root: PathBuf = resolve_prebuilt_root(&v8_dst);
+        println!("Resolving v8 prebuilt {filename} (tag {effective_tag}, profile {profile})");
+        match prepare_prebuilt(&bases, &filename, &effective_tag, &target_triple, profile, &v8_dst) {
+            Ok(()) => resolve_prebuilt_root(&v8_dst),
+            Err(err) => {
+                // No published asset for this target: fall back to a source
+                // build if a checkout is present, otherwise fail as before.
+                println!(
+                    "cargo:warning=Could not fetch v8 prebuilt ({err}); attempting source build"
+                );
+                build_v8_from_source(&manifest_dir, &out_dir, &target_triple).expect(
+                    "Failed to download or extract v8 prebuilt and no third_party/v8 source build \
+                     was possible. Please check if your system and architecture are supported.",
+                )
+            }
+        }
+    };
 
     // Erwartete Layout nach dem Extrahieren:
     // v8-prebuilt/include/...
@@ -528,38 +1742,74 @@ fn main() {
     // Platform-specific library naming
     let is_windows: bool = cargo_target.contains("windows");
     let is_macos: bool = cargo_target.contains("apple-darwin");
-    let is_linux: bool = cargo_target.contains("linux");
-    let is_musl: bool = cargo_target.contains("musl");
-    // Prefer new unified lib/ directory layout (monolith inside lib/). Retain backward-compatible root fallbacks.
-    let lib_candidates: Vec<PathBuf> = if is_windows {
+    // `CARGO_CFG_TARGET_OS`/`CARGO_CFG_TARGET_ENV` are always set for build
+    // scripts (including cross-compiles) and drive the system-link table
+    // below; fall back to sniffing `TARGET` only if they are somehow absent.
+    let target_os: String = env::var("CARGO_CFG_TARGET_OS").unwrap_or_else(|_| {
+        if is_windows {
+            "windows".to_string()
+        } else if is_macos {
+            "macos".to_string()
+        } else if cargo_target.contains("linux") {
+            "linux".to_string()
+        } else {
+            String::new()
+        }
+    });
+    let target_env: String = env::var("CARGO_CFG_TARGET_ENV")
+        .unwrap_or_else(|_| if cargo_target.contains("musl") { "musl".to_string() } else { String::new() });
+    let (cxx_mode, cxx_mode_explicit): (CxxLinkMode, bool) = cxx_link_mode(&target_os);
+    // Prefer the unified lib/ directory layout (monolith inside lib/). Retain
+    // backward-compatible root fallbacks. Static and dynamic artifacts are
+    // resolved independently so both can be honored when present.
+    let first_existing = |candidates: &[PathBuf]| -> Option<PathBuf> {
+        candidates.iter().find(|p: &&PathBuf| p.exists()).cloned()
+    };
+
+    let static_candidates: Vec<PathBuf> = if is_windows {
         vec![
             v8_root.join("lib").join("v8_monolith.lib"),
             v8_root.join("v8_monolith.lib"),
         ]
-    } else if is_macos {
+    } else {
         vec![
             v8_root.join("lib").join("libv8_monolith.a"),
             v8_root.join("libv8_monolith.a"),
+        ]
+    };
+    let dynamic_candidates: Vec<PathBuf> = if is_windows {
+        Vec::new()
+    } else if is_macos {
+        vec![
             v8_root.join("lib").join("libv8_monolith.dylib"),
             v8_root.join("libv8_monolith.dylib"),
         ]
     } else {
         vec![
-            v8_root.join("lib").join("libv8_monolith.a"),
-            v8_root.join("libv8_monolith.a"),
             v8_root.join("lib").join("libv8_monolith.so"),
             v8_root.join("libv8_monolith.so"),
         ]
     };
-    let lib_path: PathBuf = lib_candidates
-        .into_iter()
-        .find(|p: &PathBuf| p.exists())
-        .unwrap_or_else(|| {
-            panic!(
+
+    let static_lib: Option<PathBuf> = first_existing(&static_candidates);
+    let dynamic_lib: Option<PathBuf> = first_existing(&dynamic_candidates);
+
+    let link_policy: LinkPolicy = parse_link_policy();
+
+    // The artifact that drives name/directory derivation depends on the policy;
+    // an explicit dynamic request prefers the shared library when it exists.
+    let lib_path: PathBuf = match link_policy {
+        LinkPolicy::Dynamic | LinkPolicy::PreferDynamic | LinkPolicy::Both => {
+            dynamic_lib.clone().or_else(|| static_lib.clone())
+        }
+        LinkPolicy::Static | LinkPolicy::Auto => static_lib.clone().or_else(|| dynamic_lib.clone()),
+    }
+    .unwrap_or_else(|| {
+        panic!(
             "Could not find v8 monolithic library in prebuilt at {}. Expected lib in root or lib/.",
             v8_root.display()
         )
-        });
+    });
 
     if let Some(icu_src) = find_icudtl_dat(&v8_root, &manifest_dir) {
         let icu_dst: PathBuf = out_dir.join("icudtl.dat");
@@ -599,13 +1849,6 @@ fn main() {
         lib_name = stripped;
     }
 
-    let link_kind: String = env::var("V8_LINK_KIND").unwrap_or_else(|_| {
-        match lib_path.extension().and_then(|ext: &OsStr| ext.to_str()) {
-            Some("so") | Some("dylib") => "dylib".to_string(),
-            _ => "static".to_string(),
-        }
-    });
-
     // Compile shim.cc and link against prebuilt V8
     let mut build: cc::Build = cc::Build::new();
     build.cpp(true).include("src/cpp").include(&include_path);
@@ -628,6 +1871,17 @@ fn main() {
         build.flag_if_supported("-std=c++20");
     }
 
+    // Position-independent code. `cc` already enables PIC for most targets, but
+    // the override below makes the decision explicit so 32-bit cdylib/PIE links
+    // don't silently break and users can force it either way.
+    if !is_msvc {
+        let pic: bool = wants_pic(is_windows);
+        build.pic(pic);
+        if pic {
+            build.flag_if_supported("-fPIC");
+        }
+    }
+
     if let Some((config, config_path)) = config_info {
         println!("cargo:rerun-if-changed={}", config_path.display());
         if config
@@ -651,6 +1905,10 @@ fn main() {
         {
             build.define("V8_ENABLE_SANDBOX", None);
         }
+
+        for flag in cpu_baseline_flags(&config, &cargo_target, is_msvc) {
+            build.flag_if_supported(&flag);
+        }
     }
 
     // If there are additional platform flags, add them via env vars if needed
@@ -674,6 +1932,7 @@ fn main() {
     }
 
     let local_v8_out: PathBuf = manifest_dir.join("third_party").join("v8").join("out.gn");
+    let mut bundled_libcxx_found: bool = false;
     if local_v8_out.exists() {
         if let Ok(builds) = fs::read_dir(&local_v8_out) {
             for build in builds.flatten() {
@@ -738,7 +1997,7 @@ fn main() {
                     }
                 }
 
-                if is_windows {
+                if cxx_mode == CxxLinkMode::Bundled {
                     let libcxx_obj: PathBuf = obj_dir
                         .join("buildtools")
                         .join("third_party")
@@ -755,6 +2014,7 @@ fn main() {
                             &manifest_dir,
                             &lib_basename,
                         ) {
+                            bundled_libcxx_found = true;
                             if let Some(parent) = lib_path.parent() {
                                 if !link_search_dirs.iter().any(|p: &PathBuf| p == parent) {
                                     link_search_dirs.push(parent.to_path_buf());
@@ -770,41 +2030,138 @@ fn main() {
         }
     }
 
+    if cxx_mode == CxxLinkMode::Bundled && cxx_mode_explicit && !bundled_libcxx_found {
+        panic!(
+            "V8_LIBCXX=bundled (or the bundled-libcxx feature) was requested but no \
+             buildtools/third_party/libc++/libc++ objects were found under any \
+             third_party/v8/out.gn/*/obj directory; build V8 from source with \
+             use_custom_libcxx=true or switch to system-cxx."
+        );
+    }
+
     extra_libs.remove(lib_name);
 
     for dir in &link_search_dirs {
         println!("cargo:rustc-link-search=native={}", dir.display());
     }
-    println!("cargo:rustc-link-lib={link_kind}={lib_name}");
 
-    let mut extra_pairs: Vec<_> = extra_libs.into_iter().collect();
-    extra_pairs.sort_by(|a: &(String, &str), b: &(String, &str)| a.0.cmp(&b.0));
-    for (name, kind) in extra_pairs {
-        println!("cargo:rustc-link-lib={kind}={name}");
+    // Resolve the policy against what the prebuilt actually ships, then emit the
+    // monolith link directive(s). `link_dynamically` also drives the rpath below.
+    let link_dynamically: bool = match link_policy {
+        LinkPolicy::Static => false,
+        LinkPolicy::Dynamic => true,
+        LinkPolicy::PreferDynamic | LinkPolicy::Both => dynamic_lib.is_some(),
+        LinkPolicy::Auto => static_lib.is_none() && dynamic_lib.is_some(),
+    };
+
+    if link_dynamically {
+        println!("cargo:rustc-link-lib=dylib={lib_name}");
+    } else {
+        println!("cargo:rustc-link-lib=static={lib_name}");
     }
 
-    if is_windows {
-        for lib in [
-            "dbghelp", "winmm", "ws2_32", "user32", "advapi32", "ole32", "oleaut32", "shell32",
-        ] {
-            println!("cargo:rustc-link-lib=dylib={lib}");
-        }
-    } else if is_macos {
-        for lib in ["c++", "m", "pthread"] {
-            println!("cargo:rustc-link-lib=dylib={lib}");
-        }
-        for framework in ["CoreFoundation", "CoreServices"] {
-            println!("cargo:rustc-link-lib=framework={framework}");
+    // Record the path of every artifact present, including the one we did
+    // *not* link, so consumers (and the C-ABI bundling) can still find it;
+    // `both` additionally keeps the static directory on the search path.
+    if let Some(path) = &static_lib {
+        println!("cargo:rustc-env=PACM_V8_MONOLITH_STATIC={}", path.display());
+        if link_policy == LinkPolicy::Both {
+            if let Some(parent) = path.parent() {
+                println!("cargo:rustc-link-search=native={}", parent.display());
+            }
         }
-    } else if is_linux {
-        for lib in ["stdc++", "m", "pthread", "dl"] {
-            println!("cargo:rustc-link-lib=dylib={lib}");
+    }
+    if let Some(path) = &dynamic_lib {
+        println!("cargo:rustc-env=PACM_V8_MONOLITH_DYNAMIC={}", path.display());
+    }
+
+    // When linking the shared V8 the produced binary needs an rpath to find it
+    // at runtime — the static path never does. Emit a loader-relative rpath
+    // (the binary's own directory) and an absolute rpath for the directory the
+    // shared library lives in.
+    if link_dynamically && !is_windows {
+        if let Some(dir) = dynamic_lib.as_ref().and_then(|p| p.parent()) {
+            let loader_relative: &str = if is_macos { "@loader_path" } else { "$ORIGIN" };
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{loader_relative}");
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
         }
-        if !is_musl {
-            println!("cargo:rustc-link-lib=dylib=rt");
+    }
+
+    let mut extra_pairs: Vec<(String, &'static str)> = extra_libs.into_iter().collect();
+    extra_pairs.sort_by(|a: &(String, &str), b: &(String, &str)| a.0.cmp(&b.0));
+    for (name, kind) in &extra_pairs {
+        println!("cargo:rustc-link-lib={kind}={name}");
+    }
+
+    let platform_link: SystemLink =
+        resolve_system_link(&v8_root, &manifest_dir, &target_os, &target_env);
+    for (lib, kind) in &platform_link.libs {
+        println!("cargo:rustc-link-lib={kind}={lib}");
+    }
+    // The bundled archive (added to extra_pairs above via `v8_libcxx_*`) stands
+    // in for the system C++ runtime; only link the latter in `system` mode.
+    if cxx_mode == CxxLinkMode::System {
+        if let Some(cxx) = &platform_link.cxx_runtime {
+            println!("cargo:rustc-link-lib=dylib={cxx}");
         }
     }
+    for framework in &platform_link.frameworks {
+        println!("cargo:rustc-link-lib=framework={framework}");
+    }
 
     // Provide include location for crate users (optional)
     println!("cargo:include={}", include_path.display());
+
+    // Optionally emit cinstall-style C-ABI artifacts so C/C++ consumers can
+    // embed the same prebuilt V8 without re-deriving the transitive link flags.
+    if cabi_requested() {
+        emit_cabi_artifacts(&CabiInputs {
+            out_dir: &out_dir,
+            crate_version: &crate_version,
+            include_path: &include_path,
+            link_search_dirs: &link_search_dirs,
+            monolith: lib_name,
+            extra_libs: &extra_pairs,
+            is_windows,
+            is_macos,
+            system_link: &platform_link,
+            cxx_mode,
+        });
+    }
+}
+
+// `cargo test --workspace` does not run a build script's own test harness by
+// default, but the checksum routine below is pure and otherwise untestable in
+// isolation — keep the NIST/FIPS 180-4 test vectors here so `cargo test -p
+// pacm-v8 --bin build-script-build` (or copying the function out) has
+// something to check it against.
+#[cfg(test)]
+mod tests {
+    use super::sha256_hex;
+
+    #[test]
+    fn sha256_of_empty_string() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn sha256_of_abc() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn sha256_of_two_block_message() {
+        assert_eq!(
+            sha256_hex(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            ),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
 }
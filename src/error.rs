@@ -1,40 +1,336 @@
-use std::fmt;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct V8Error {
-    message: String,
-}
-
-impl V8Error {
-    pub fn new(message: impl Into<String>) -> Self {
-        Self {
-            message: message.into(),
-        }
-    }
-
-    pub fn message(&self) -> &str {
-        &self.message
-    }
-}
-
-impl fmt::Display for V8Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
-    }
-}
-
-impl std::error::Error for V8Error {}
-
-impl From<&str> for V8Error {
-    fn from(value: &str) -> Self {
-        Self::new(value)
-    }
-}
-
-impl From<String> for V8Error {
-    fn from(value: String) -> Self {
-        Self::new(value)
-    }
-}
-
-pub type Result<T> = std::result::Result<T, V8Error>;
+use std::fmt;
+
+/// Which JS error constructor an error maps to.
+///
+/// Used in both directions: it selects the constructor the bridge throws when a
+/// host callback returns an error, and records the class of a JS exception
+/// captured on the way back out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    Error,
+    TypeError,
+    RangeError,
+    SyntaxError,
+    ReferenceError,
+    /// A constructor the bridge does not model explicitly; the wrapped string
+    /// is the JS error's `name`.
+    Other(String),
+}
+
+impl ErrorKind {
+    /// Wire byte describing the kind across the FFI boundary.
+    pub(crate) fn wire_byte(&self) -> u8 {
+        match self {
+            ErrorKind::Error => 0,
+            ErrorKind::TypeError => 1,
+            ErrorKind::RangeError => 2,
+            ErrorKind::SyntaxError => 3,
+            ErrorKind::ReferenceError => 4,
+            ErrorKind::Other(_) => 5,
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "Error" => ErrorKind::Error,
+            "TypeError" => ErrorKind::TypeError,
+            "RangeError" => ErrorKind::RangeError,
+            "SyntaxError" => ErrorKind::SyntaxError,
+            "ReferenceError" => ErrorKind::ReferenceError,
+            other => ErrorKind::Other(other.to_string()),
+        }
+    }
+
+    /// The JS constructor name this kind corresponds to.
+    pub fn as_name(&self) -> &str {
+        match self {
+            ErrorKind::Error => "Error",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::RangeError => "RangeError",
+            ErrorKind::SyntaxError => "SyntaxError",
+            ErrorKind::ReferenceError => "ReferenceError",
+            ErrorKind::Other(name) => name,
+        }
+    }
+}
+
+/// An error crossing the V8 boundary.
+///
+/// Failures used to collapse into a single message string. The structured form
+/// keeps the JS error's constructor and, for exceptions captured out of
+/// `eval`/`Script::run`, the formatted stack trace plus the script's resource
+/// name, line number, and start column (see [`V8Error::location`]); it
+/// crosses the C ABI as a small length-prefixed encoded structure rather than
+/// a bare C string. `Display` still prints only the message for backward
+/// compatibility.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V8Error {
+    kind: ErrorKind,
+    message: String,
+    stack: Option<String>,
+    script_resource_name: Option<String>,
+    line_number: Option<i32>,
+    start_column: Option<i32>,
+}
+
+/// Where in a script a captured JS exception was raised.
+///
+/// Returned by [`V8Error::location`]; absent when the error didn't originate
+/// from compiling or running a script (e.g. one built with [`V8Error::new`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+    /// The resource name the script was compiled with — see
+    /// [`crate::Script::compile_named`]. `None` for anonymous scripts.
+    pub script_resource_name: Option<String>,
+    pub line_number: i32,
+    pub start_column: i32,
+}
+
+impl V8Error {
+    /// A generic `Error` carrying only a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Error,
+            message: message.into(),
+            stack: None,
+            script_resource_name: None,
+            line_number: None,
+            start_column: None,
+        }
+    }
+
+    /// An error of a specific JS constructor kind.
+    pub fn of_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            stack: None,
+            script_resource_name: None,
+            line_number: None,
+            start_column: None,
+        }
+    }
+
+    /// Attach a stack trace, consuming and returning `self` (builder style).
+    pub fn with_stack(mut self, stack: impl Into<String>) -> Self {
+        self.stack = Some(stack.into());
+        self
+    }
+
+    /// Attach the script location the exception was raised at, consuming and
+    /// returning `self` (builder style). `resource_name` is empty for a
+    /// script compiled without one (see [`crate::Script::compile_named`]).
+    pub fn with_location(
+        mut self,
+        resource_name: impl Into<String>,
+        line_number: i32,
+        start_column: i32,
+    ) -> Self {
+        let resource_name = resource_name.into();
+        self.script_resource_name = if resource_name.is_empty() {
+            None
+        } else {
+            Some(resource_name)
+        };
+        self.line_number = Some(line_number);
+        self.start_column = Some(start_column);
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// The JS error constructor name (`"TypeError"`, `"Error"`, …).
+    pub fn name(&self) -> &str {
+        self.kind.as_name()
+    }
+
+    /// The captured JS stack trace, when the failure originated in JS.
+    pub fn stack_trace(&self) -> Option<&str> {
+        self.stack.as_deref()
+    }
+
+    /// Where the exception was raised, when the failure originated from
+    /// compiling or running a script.
+    pub fn location(&self) -> Option<ErrorLocation> {
+        let line_number = self.line_number?;
+        let start_column = self.start_column?;
+        Some(ErrorLocation {
+            script_resource_name: self.script_resource_name.clone(),
+            line_number,
+            start_column,
+        })
+    }
+
+    /// Encode into the length-prefixed structure understood by the shim.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer.push(self.kind.wire_byte());
+        put_str(&mut buffer, self.name());
+        put_str(&mut buffer, &self.message);
+        put_str(&mut buffer, self.stack.as_deref().unwrap_or(""));
+        put_str(&mut buffer, self.script_resource_name.as_deref().unwrap_or(""));
+        buffer.extend_from_slice(&self.line_number.unwrap_or(-1).to_le_bytes());
+        buffer.extend_from_slice(&self.start_column.unwrap_or(-1).to_le_bytes());
+        buffer
+    }
+
+    /// Decode the structure produced by the shim, or fall back to `fallback`
+    /// when the buffer is absent or malformed.
+    pub(crate) fn decode(bytes: &[u8], fallback: &str) -> Self {
+        let mut cursor = Reader::new(bytes);
+        let decoded = (|| {
+            // The constructor kind is carried by its name, so the leading byte
+            // is only consumed for alignment with the encoded layout.
+            let _kind_byte = cursor.u8()?;
+            let name = cursor.string()?;
+            let message = cursor.string()?;
+            let stack = cursor.string()?;
+            let script_resource_name = cursor.string()?;
+            let line_number = cursor.i32()?;
+            let start_column = cursor.i32()?;
+            Some(Self {
+                kind: ErrorKind::from_name(&name),
+                message,
+                stack: if stack.is_empty() { None } else { Some(stack) },
+                script_resource_name: if script_resource_name.is_empty() {
+                    None
+                } else {
+                    Some(script_resource_name)
+                },
+                line_number: if line_number < 0 { None } else { Some(line_number) },
+                start_column: if start_column < 0 { None } else { Some(start_column) },
+            })
+        })();
+        decoded.unwrap_or_else(|| Self::new(fallback))
+    }
+}
+
+impl fmt::Display for V8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for V8Error {}
+
+impl From<&str> for V8Error {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for V8Error {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, V8Error>;
+
+fn put_str(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.offset)?;
+        self.offset += 1;
+        Some(byte)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        let bytes = self.bytes.get(self.offset..self.offset + 4)?;
+        let value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        self.offset += 4;
+        Some(value)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len_bytes = self.bytes.get(self.offset..self.offset + 4)?;
+        let len =
+            u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+        self.offset += 4;
+        let slice = self.bytes.get(self.offset..self.offset + len)?;
+        self.offset += len;
+        Some(String::from_utf8_lossy(slice).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorKind, V8Error};
+
+    #[test]
+    fn round_trips_a_plain_message() {
+        let error = V8Error::new("boom");
+        let decoded = V8Error::decode(&error.encode(), "fallback");
+        assert_eq!(decoded, error);
+        assert_eq!(decoded.message(), "boom");
+        assert_eq!(decoded.name(), "Error");
+        assert_eq!(decoded.stack_trace(), None);
+        assert_eq!(decoded.location(), None);
+    }
+
+    #[test]
+    fn round_trips_kind_stack_and_location() {
+        let error = V8Error::of_kind(ErrorKind::TypeError, "not a function")
+            .with_stack("TypeError: not a function\n    at <anonymous>:1:1")
+            .with_location("main.js", 1, 1);
+        let decoded = V8Error::decode(&error.encode(), "fallback");
+        assert_eq!(decoded, error);
+        assert_eq!(decoded.name(), "TypeError");
+        assert_eq!(
+            decoded.stack_trace(),
+            Some("TypeError: not a function\n    at <anonymous>:1:1")
+        );
+        let location = decoded.location().expect("location should round-trip");
+        assert_eq!(location.script_resource_name.as_deref(), Some("main.js"));
+        assert_eq!(location.line_number, 1);
+        assert_eq!(location.start_column, 1);
+    }
+
+    #[test]
+    fn round_trips_a_custom_error_kind() {
+        let error = V8Error::of_kind(ErrorKind::Other("AggregateError".to_string()), "multiple errors");
+        let decoded = V8Error::decode(&error.encode(), "fallback");
+        assert_eq!(decoded.name(), "AggregateError");
+        assert_eq!(decoded.kind(), &ErrorKind::Other("AggregateError".to_string()));
+    }
+
+    #[test]
+    fn decode_falls_back_on_malformed_input() {
+        let decoded = V8Error::decode(&[1, 2, 3], "decode failed");
+        assert_eq!(decoded.message(), "decode failed");
+        assert_eq!(decoded.kind(), &ErrorKind::Error);
+    }
+
+    #[test]
+    fn decode_falls_back_on_empty_input() {
+        let decoded = V8Error::decode(&[], "decode failed");
+        assert_eq!(decoded.message(), "decode failed");
+    }
+
+    #[test]
+    fn display_prints_only_the_message() {
+        let error = V8Error::of_kind(ErrorKind::RangeError, "out of range")
+            .with_stack("RangeError: out of range")
+            .with_location("main.js", 5, 2);
+        assert_eq!(error.to_string(), "out of range");
+    }
+}
@@ -0,0 +1,45 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// A parking waker that unblocks the thread currently driving a future.
+///
+/// The bridge has no async runtime of its own; async host callbacks are driven
+/// to completion on a dedicated host-executor thread (see
+/// [`crate::native::spawn_async`]). This waker is all that path needs — it just
+/// re-wakes the blocked driver thread when a future signals readiness.
+struct ThreadWaker {
+    thread: Thread,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.thread.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.thread.unpark();
+    }
+}
+
+/// Drive `future` to completion on the current thread, parking between polls.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker: Waker = Arc::new(ThreadWaker {
+        thread: thread::current(),
+    })
+    .into();
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Convenience alias for the boxed, sendable futures async host callbacks yield.
+pub(crate) type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::slice;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::error::{Result, V8Error};
+
+/// How a 64-bit integer scalar is surfaced to JS on the fast path.
+///
+/// Mirrors rusty_v8's `Int64Representation`: `Number` accepts/produces a JS
+/// `number` (lossy beyond 2^53), `BigInt` uses a JS `BigInt` for full range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Int64Representation {
+    Number,
+    BigInt,
+}
+
+/// A scalar type admissible in a fast-call signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastType {
+    I32,
+    F64,
+    I64(Int64Representation),
+    U64(Int64Representation),
+    Bool,
+}
+
+/// A scalar value passed by value across the fast-call boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FastValue {
+    I32(i32),
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    Bool(bool),
+}
+
+/// A declared fast-call signature: the parameter scalar types and the return
+/// scalar type. Calls whose runtime argument types don't match fall back to the
+/// ordinary encoded-value path.
+#[derive(Debug, Clone)]
+pub struct FastSignature {
+    pub params: Vec<FastType>,
+    pub ret: FastType,
+}
+
+impl FastType {
+    /// The wire byte describing this type to the shim when it builds the
+    /// `v8::CFunction`.
+    pub(crate) fn wire_byte(self) -> u8 {
+        match self {
+            FastType::I32 => 0,
+            FastType::F64 => 1,
+            FastType::I64(Int64Representation::BigInt) => 2,
+            FastType::I64(Int64Representation::Number) => 3,
+            FastType::U64(Int64Representation::BigInt) => 4,
+            FastType::U64(Int64Representation::Number) => 5,
+            FastType::Bool => 6,
+        }
+    }
+}
+
+/// A raw scalar as it crosses the C ABI: a tag plus the value's bit pattern.
+/// Passing these by value avoids the argument-buffer encoding and allocation
+/// the slow path incurs.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FastScalar {
+    pub tag: u32,
+    pub bits: u64,
+}
+
+mod scalar_tag {
+    pub(super) const I32: u32 = 0;
+    pub(super) const F64: u32 = 1;
+    pub(super) const I64: u32 = 2;
+    pub(super) const U64: u32 = 3;
+    pub(super) const BOOL: u32 = 4;
+}
+
+impl FastScalar {
+    fn to_value(self) -> Option<FastValue> {
+        match self.tag {
+            scalar_tag::I32 => Some(FastValue::I32(self.bits as u32 as i32)),
+            scalar_tag::F64 => Some(FastValue::F64(f64::from_bits(self.bits))),
+            scalar_tag::I64 => Some(FastValue::I64(self.bits as i64)),
+            scalar_tag::U64 => Some(FastValue::U64(self.bits)),
+            scalar_tag::BOOL => Some(FastValue::Bool(self.bits != 0)),
+            _ => None,
+        }
+    }
+
+    fn from_value(value: FastValue) -> Self {
+        match value {
+            FastValue::I32(v) => FastScalar {
+                tag: scalar_tag::I32,
+                bits: v as u32 as u64,
+            },
+            FastValue::F64(v) => FastScalar {
+                tag: scalar_tag::F64,
+                bits: v.to_bits(),
+            },
+            FastValue::I64(v) => FastScalar {
+                tag: scalar_tag::I64,
+                bits: v as u64,
+            },
+            FastValue::U64(v) => FastScalar {
+                tag: scalar_tag::U64,
+                bits: v,
+            },
+            FastValue::Bool(v) => FastScalar {
+                tag: scalar_tag::BOOL,
+                bits: u64::from(v),
+            },
+        }
+    }
+}
+
+type FastCallback = dyn Fn(&[FastValue]) -> Result<FastValue> + Send + Sync + 'static;
+
+struct FastEntry {
+    signature: FastSignature,
+    callback: Arc<FastCallback>,
+}
+
+static REGISTRY: OnceLock<Mutex<HashMap<u64, FastEntry>>> = OnceLock::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn registry() -> &'static Mutex<HashMap<u64, FastEntry>> {
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn register<F>(signature: FastSignature, callback: F) -> u64
+where
+    F: Fn(&[FastValue]) -> Result<FastValue> + Send + Sync + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    registry().lock().unwrap().insert(
+        id,
+        FastEntry {
+            signature,
+            callback: Arc::from(Box::new(callback) as Box<FastCallback>),
+        },
+    );
+    id
+}
+
+pub(crate) fn drop_function(id: u64) {
+    if let Some(lock) = REGISTRY.get() {
+        lock.lock().unwrap().remove(&id);
+    }
+}
+
+/// Fast-call entry point. Scalars arrive by value; the result scalar is written
+/// through `result_out`. Returns 0 (so the shim retries via the slow
+/// encoded-value path) when the id is unknown, arity/type don't match the declared signature,
+/// or the callback fails.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__fast_invoke(
+    id: u64,
+    args: *const FastScalar,
+    arg_count: usize,
+    result_out: *mut FastScalar,
+) -> i32 {
+    let entry = {
+        let guard = registry().lock().unwrap();
+        match guard.get(&id) {
+            Some(entry) => (entry.signature.clone(), Arc::clone(&entry.callback)),
+            None => return 0,
+        }
+    };
+    let (signature, callback) = entry;
+
+    if arg_count != signature.params.len() {
+        return 0;
+    }
+
+    let raw = if args.is_null() || arg_count == 0 {
+        &[][..]
+    } else {
+        unsafe { slice::from_raw_parts(args, arg_count) }
+    };
+
+    let mut values = Vec::with_capacity(arg_count);
+    for (scalar, expected) in raw.iter().zip(&signature.params) {
+        let expected_tag = match expected {
+            FastType::I32 => scalar_tag::I32,
+            FastType::F64 => scalar_tag::F64,
+            FastType::I64(_) => scalar_tag::I64,
+            FastType::U64(_) => scalar_tag::U64,
+            FastType::Bool => scalar_tag::BOOL,
+        };
+        if scalar.tag != expected_tag {
+            return 0;
+        }
+        match scalar.to_value() {
+            Some(value) => values.push(value),
+            None => return 0,
+        }
+    }
+
+    match callback(&values) {
+        Ok(result) => {
+            if !result_out.is_null() {
+                unsafe {
+                    *result_out = FastScalar::from_value(result);
+                }
+            }
+            1
+        }
+        Err(_) => 0,
+    }
+}
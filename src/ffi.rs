@@ -1,68 +1,237 @@
-use std::os::raw::{c_char, c_double};
-
-pub type V8IsolateHandle = *mut std::ffi::c_void;
-pub type V8ContextHandle = *mut std::ffi::c_void;
-pub type V8ScriptHandle = *mut std::ffi::c_void;
-
-unsafe extern "C" {
-    pub fn shim_v8_initialize(icu_data_path: *const c_char) -> i32;
-
-    pub fn shim_create_isolate() -> V8IsolateHandle;
-    pub fn shim_dispose_isolate(isolate: V8IsolateHandle);
-
-    pub fn shim_create_context(isolate: V8IsolateHandle) -> V8ContextHandle;
-    pub fn shim_dispose_context(context: V8ContextHandle);
-
-    pub fn shim_context_eval(
-        context: V8ContextHandle,
-        source: *const c_char,
-        result_out: *mut *mut c_char,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_context_set_global_string(
-        context: V8ContextHandle,
-        name: *const c_char,
-        value: *const c_char,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_context_set_global_number(
-        context: V8ContextHandle,
-        name: *const c_char,
-        value: c_double,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_context_register_host_function(
-        context: V8ContextHandle,
-        name: *const c_char,
-        function_id: u64,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_context_call_function(
-        context: V8ContextHandle,
-        fn_name: *const c_char,
-        args: *const *const c_char,
-        arg_count: usize,
-        result_out: *mut *mut c_char,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_compile_script(
-        isolate: V8IsolateHandle,
-        source: *const c_char,
-        error_out: *mut *mut c_char,
-    ) -> V8ScriptHandle;
-
-    pub fn shim_script_run(
-        script: V8ScriptHandle,
-        context: V8ContextHandle,
-        result_out: *mut *mut c_char,
-        error_out: *mut *mut c_char,
-    ) -> i32;
-
-    pub fn shim_script_dispose(script: V8ScriptHandle);
-    pub fn shim_free_string(ptr: *mut c_char);
-}
+use std::os::raw::{c_char, c_double};
+
+pub type V8IsolateHandle = *mut std::ffi::c_void;
+pub type V8ContextHandle = *mut std::ffi::c_void;
+pub type V8ScriptHandle = *mut std::ffi::c_void;
+
+unsafe extern "C" {
+    pub fn shim_v8_initialize(icu_data_path: *const c_char) -> i32;
+
+    // Returns the linked V8 version string (e.g. "12.3.219.9"), owned by V8 and
+    // valid for the process lifetime — never freed by the caller.
+    pub fn shim_v8_version() -> *const c_char;
+
+    pub fn shim_create_isolate() -> V8IsolateHandle;
+    pub fn shim_dispose_isolate(isolate: V8IsolateHandle);
+
+    // Creates an isolate in snapshot-creation mode. Setup (contexts, globals,
+    // prelude scripts) runs against it exactly like a normal isolate; the heap
+    // is then serialised by `shim_create_snapshot`.
+    pub fn shim_create_snapshot_creator() -> V8IsolateHandle;
+
+    // Serialises the creator isolate's heap into a freshly allocated blob and
+    // disposes the creator. The blob is released by the caller via
+    // `shim_free_buffer`.
+    pub fn shim_create_snapshot(
+        isolate: V8IsolateHandle,
+        blob_out: *mut *mut u8,
+        blob_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Boots a fresh isolate whose heap is restored from `blob`. The caller must
+    // keep `blob` alive for the isolate's lifetime.
+    pub fn shim_create_isolate_from_snapshot(blob: *const u8, blob_len: usize) -> V8IsolateHandle;
+
+    pub fn shim_create_context(isolate: V8IsolateHandle) -> V8ContextHandle;
+    pub fn shim_dispose_context(context: V8ContextHandle);
+
+    pub fn shim_context_eval(
+        context: V8ContextHandle,
+        source: *const c_char,
+        result_out: *mut *mut u8,
+        result_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_context_set_global_string(
+        context: V8ContextHandle,
+        name: *const c_char,
+        value: *const c_char,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_context_set_global_number(
+        context: V8ContextHandle,
+        name: *const c_char,
+        value: c_double,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Sets a global to an arbitrary encoded `JsValue` buffer, decoded and
+    // converted to its JS equivalent the same way a host-function return
+    // value is. Backs the serde-typed overload of `set_global_*`.
+    pub fn shim_context_set_global_value(
+        context: V8ContextHandle,
+        name: *const c_char,
+        value: *const u8,
+        value_len: usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_context_register_host_function(
+        context: V8ContextHandle,
+        name: *const c_char,
+        function_id: u64,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Installs a global that, when called from JS, synthesises a Promise and
+    // invokes `pacm_v8__host_function_invoke_async` with a fresh resolver id;
+    // the Promise is returned to JS immediately.
+    pub fn shim_context_register_async_function(
+        context: V8ContextHandle,
+        name: *const c_char,
+        function_id: u64,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Installs a fast-call function backed by a `v8::CFunction` built from the
+    // declared scalar signature. When JS calls it with matching argument types
+    // V8 dispatches through `pacm_v8__fast_invoke`; otherwise it falls back to
+    // the ordinary (slow) host-function path.
+    pub fn shim_context_register_fast_function(
+        context: V8ContextHandle,
+        name: *const c_char,
+        function_id: u64,
+        params: *const u8,
+        param_count: usize,
+        return_type: u8,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Settles a pending Promise created by the async bridge. `is_ok` selects
+    // resolve vs reject; the payload is an encoded value buffer (resolve) or a
+    // UTF-8 message (reject).
+    pub fn shim_settle_promise(
+        context: V8ContextHandle,
+        resolver_id: u64,
+        is_ok: i32,
+        value: *const u8,
+        value_len: usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_context_call_function(
+        context: V8ContextHandle,
+        fn_name: *const c_char,
+        args: *const u8,
+        args_len: usize,
+        result_out: *mut *mut u8,
+        result_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Persists a `Global<Function>` resolved from `name` in the owning context
+    // and returns an opaque reference id (0 on failure). Released with
+    // `shim_context_release_function`.
+    pub fn shim_context_acquire_function(
+        context: V8ContextHandle,
+        name: *const c_char,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> u64;
+
+    pub fn shim_context_release_function(context: V8ContextHandle, func_ref: u64);
+
+    // Calls a previously acquired JS function. Must run on the isolate's own
+    // thread; the threadsafe queue is what marshals cross-thread callers here.
+    pub fn shim_context_call_js_function(
+        context: V8ContextHandle,
+        func_ref: u64,
+        args: *const u8,
+        args_len: usize,
+        result_out: *mut *mut u8,
+        result_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Compiles, instantiates, and evaluates an ES module. Import specifiers are
+    // resolved by calling back into `pacm_v8__resolve_module` with `resolver_id`
+    // (0 = no resolver registered). The module namespace object is returned as
+    // an encoded value buffer.
+    pub fn shim_context_eval_module(
+        context: V8ContextHandle,
+        specifier: *const c_char,
+        source: *const c_char,
+        resolver_id: u64,
+        result_out: *mut *mut u8,
+        result_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_compile_script(
+        isolate: V8IsolateHandle,
+        source: *const c_char,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> V8ScriptHandle;
+
+    // Like `shim_compile_script`, but names the compiled script
+    // `resource_name` so exceptions raised while running it (and nested
+    // stack frames) report a readable file name instead of `<unknown>`.
+    pub fn shim_compile_script_named(
+        isolate: V8IsolateHandle,
+        source: *const c_char,
+        resource_name: *const c_char,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> V8ScriptHandle;
+
+    pub fn shim_script_run(
+        script: V8ScriptHandle,
+        context: V8ContextHandle,
+        result_out: *mut *mut u8,
+        result_len_out: *mut usize,
+        error_out: *mut *mut u8,
+        error_len_out: *mut usize,
+    ) -> i32;
+
+    // Requests that any script currently running on `isolate` stop at its next
+    // safe point. Safe to call from any thread, including while nothing is
+    // running (the request is simply consumed by the next execution).
+    pub fn shim_isolate_terminate_execution(isolate: V8IsolateHandle);
+
+    // Cancels a termination request that has not yet been observed by a
+    // running script.
+    pub fn shim_isolate_cancel_terminate_execution(isolate: V8IsolateHandle);
+
+    // Non-zero while a termination request is unwinding JS execution on
+    // `isolate`, so a caller can distinguish that from an ordinary exception.
+    pub fn shim_isolate_is_execution_terminating(isolate: V8IsolateHandle) -> i32;
+
+    // Runs V8's microtask queue to completion (one checkpoint). Needed after
+    // `eval`/`call_function` produces a `Promise` — without it, reactions
+    // queued by `.then`/`async`/`await` never run.
+    pub fn shim_context_run_microtasks(context: V8ContextHandle);
+
+    // Reads the current state of the `Promise` persisted under `promise_ref`
+    // (see `JsValue::Promise`). Returns 0 = pending, 1 = fulfilled, 2 =
+    // rejected; for the latter two, `value_out` receives the encoded
+    // `JsValue` of the fulfillment or rejection value.
+    pub fn shim_promise_state(
+        context: V8ContextHandle,
+        promise_ref: u64,
+        value_out: *mut *mut u8,
+        value_len_out: *mut usize,
+    ) -> i32;
+
+    pub fn shim_script_dispose(script: V8ScriptHandle);
+    pub fn shim_free_string(ptr: *mut c_char);
+    // Encoded value buffers handed back by the shim are released here; the
+    // reverse direction (buffers minted in Rust) is freed by the host through
+    // `pacm_v8__buffer_free`.
+    pub fn shim_free_buffer(ptr: *mut u8, len: usize);
+}
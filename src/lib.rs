@@ -1,10 +1,18 @@
 mod error;
+mod executor;
+mod fast;
 mod ffi;
+mod module;
 mod native;
 mod support;
+mod termination;
+mod threadsafe;
 mod value;
 
-pub use crate::error::{Result, V8Error};
+pub use crate::error::{ErrorKind, ErrorLocation, Result, V8Error};
+pub use crate::fast::{FastSignature, FastType, FastValue, Int64Representation};
+pub use crate::termination::IsolateHandle;
+pub use crate::threadsafe::ThreadsafeFunction;
 pub use crate::value::JsValue;
 
 // Ensure temporal_capi symbols are linked even though they're only used by V8's C++ code
@@ -12,28 +20,49 @@ extern crate temporal_capi;
 
 use std::env;
 use std::ffi::CString;
-use std::os::raw::c_char;
 use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::ffi::{
     V8ContextHandle, V8IsolateHandle, V8ScriptHandle, shim_compile_script,
-    shim_context_call_function, shim_context_eval, shim_context_register_host_function,
-    shim_context_set_global_number, shim_context_set_global_string, shim_create_context,
-    shim_create_isolate, shim_dispose_context, shim_dispose_isolate, shim_script_dispose,
+    shim_compile_script_named,
+    shim_context_call_function, shim_context_eval, shim_context_eval_module,
+    shim_context_register_async_function,
+    shim_context_register_host_function,
+    shim_context_acquire_function, shim_context_register_fast_function,
+    shim_context_release_function,
+    shim_context_run_microtasks,
+    shim_context_set_global_number, shim_context_set_global_string, shim_context_set_global_value,
+    shim_create_context,
+    shim_create_isolate, shim_create_isolate_from_snapshot, shim_create_snapshot,
+    shim_create_snapshot_creator, shim_dispose_context, shim_dispose_isolate,
+    shim_promise_state, shim_script_dispose,
     shim_script_run, shim_v8_initialize,
 };
-use crate::support::{take_error, take_string};
+use crate::support::{take_bytes, take_error, take_value};
 
 const NULL_BYTE_MESSAGE: &str = "input contained an interior null byte";
 
 pub struct Isolate {
     handle: V8IsolateHandle,
+    // When the isolate was restored from a snapshot, V8 keeps referencing the
+    // blob for the lifetime of the isolate, so we hold the backing buffer here.
+    snapshot_blob: Option<Vec<u8>>,
+    // Set for isolates created via `for_snapshot`; contexts created from them
+    // refuse host-function registration since function pointers cannot be
+    // serialized into the snapshot blob.
+    is_snapshot_creator: bool,
 }
 
 pub struct Context {
     handle: V8ContextHandle,
     isolate: V8IsolateHandle,
     host_functions: Vec<u64>,
+    js_functions: Vec<u64>,
+    fast_functions: Vec<u64>,
+    module_resolver: Option<u64>,
+    is_snapshot_creator: bool,
 }
 
 pub struct Script {
@@ -57,6 +86,49 @@ fn resolve_icu_data_path() -> Option<String> {
     None
 }
 
+/// The linked V8 version string (e.g. `"12.3.219.9"`), used to tag startup
+/// snapshots so a blob built against a different V8 build is rejected instead
+/// of handed to `shim_create_isolate_from_snapshot`, where a format mismatch
+/// would otherwise crash the process.
+fn v8_version() -> String {
+    let ptr = unsafe { crate::ffi::shim_v8_version() };
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Prefix a raw snapshot blob with a length-prefixed copy of the linked V8
+/// version, so [`Isolate::snapshot_matches_version`]/[`Isolate::from_snapshot`]
+/// can check compatibility before handing the blob to V8.
+fn tag_snapshot_blob(raw: Vec<u8>) -> Vec<u8> {
+    tag_snapshot_blob_with_version(&v8_version(), raw)
+}
+
+/// The pure tagging logic behind [`tag_snapshot_blob`], split out so it can be
+/// exercised without the linked V8 version (i.e. without calling into the
+/// shim at all) in tests.
+fn tag_snapshot_blob_with_version(version: &str, raw: Vec<u8>) -> Vec<u8> {
+    let mut tagged = Vec::with_capacity(4 + version.len() + raw.len());
+    tagged.extend_from_slice(&(version.len() as u32).to_le_bytes());
+    tagged.extend_from_slice(version.as_bytes());
+    tagged.extend_from_slice(&raw);
+    tagged
+}
+
+/// Split a tagged snapshot blob into its recorded V8 version and the raw
+/// bytes V8 itself expects. `None` when `blob` is too short to carry a valid
+/// header (e.g. it predates this crate's version tagging).
+fn untag_snapshot_blob(blob: &[u8]) -> Option<(&str, &[u8])> {
+    let len_bytes = blob.get(0..4)?;
+    let len = u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+    let version = std::str::from_utf8(blob.get(4..4 + len)?).ok()?;
+    let raw = blob.get(4 + len..)?;
+    Some((version, raw))
+}
+
 fn initialize_v8(icu_path: Option<&str>) -> Result<()> {
     let icu_cstring = match icu_path {
         Some(path) => Some(CString::new(path).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?),
@@ -84,13 +156,142 @@ impl Isolate {
             return Err(V8Error::new("failed to create V8 isolate"));
         }
 
-        Ok(Self { handle })
+        Ok(Self {
+            handle,
+            snapshot_blob: None,
+            is_snapshot_creator: false,
+        })
+    }
+
+    /// Create an isolate in snapshot-creation mode.
+    ///
+    /// Contexts, globals, and prelude scripts are set up against the returned
+    /// isolate exactly as usual; [`Isolate::snapshot`] then serialises the
+    /// resulting heap. A snapshot-creator isolate is single-use — it must be
+    /// consumed by `snapshot` rather than handed out for long-lived work.
+    /// Contexts created from it reject host-function registration, since
+    /// function pointers cannot be serialized into the blob.
+    pub fn for_snapshot() -> Result<Self> {
+        let icu_path = resolve_icu_data_path();
+        initialize_v8(icu_path.as_deref())?;
+
+        let handle = unsafe { shim_create_snapshot_creator() };
+        if handle.is_null() {
+            return Err(V8Error::new("failed to create V8 snapshot creator"));
+        }
+
+        Ok(Self {
+            handle,
+            snapshot_blob: None,
+            is_snapshot_creator: true,
+        })
+    }
+
+    /// Serialise the creator isolate's heap into a startup blob.
+    ///
+    /// Consumes the isolate: finalising a snapshot disposes the underlying
+    /// creator in V8, so the handle must not outlive the call. The returned
+    /// bytes can be replayed with [`Isolate::from_snapshot`].
+    ///
+    /// The blob carries no host functions: contexts of a snapshot-creator
+    /// isolate reject host-function registration, so host functions are
+    /// registered on contexts of the restored isolate instead.
+    pub fn snapshot(mut self) -> Result<Vec<u8>> {
+        if self.handle.is_null() {
+            return Err(V8Error::new("isolate was disposed"));
+        }
+
+        let mut blob_ptr: *mut u8 = ptr::null_mut();
+        let mut blob_len: usize = 0;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let status = unsafe {
+            shim_create_snapshot(
+                self.handle,
+                &mut blob_ptr,
+                &mut blob_len,
+                &mut error_ptr,
+                &mut error_len,
+            )
+        };
+
+        // The shim disposes the creator regardless of outcome; neutralise our
+        // own `Drop` so we don't double-free the handle.
+        self.handle = ptr::null_mut();
+
+        if status == 0 {
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to create snapshot") });
+        }
+
+        let blob = unsafe { take_bytes(blob_ptr, blob_len) };
+        if blob.is_empty() {
+            return Err(V8Error::new("snapshot shim produced an empty blob"));
+        }
+        Ok(tag_snapshot_blob(blob))
+    }
+
+    /// Whether `blob` (as produced by [`Isolate::snapshot`]) was built against
+    /// the V8 version this crate is linked against.
+    ///
+    /// A stale cached snapshot from a different V8 build is not guaranteed to
+    /// deserialize safely, so callers should check this (or rely on
+    /// [`Isolate::from_snapshot`]'s own check) before restoring one that may
+    /// have outlived a V8 upgrade.
+    pub fn snapshot_matches_version(blob: &[u8]) -> bool {
+        match untag_snapshot_blob(blob) {
+            Some((version, _raw)) => version == v8_version(),
+            None => false,
+        }
+    }
+
+    /// Boot a fresh isolate whose heap is restored from `blob`.
+    ///
+    /// The blob is copied into the isolate so callers need not keep their own
+    /// buffer alive; the returned `Isolate` holds the copy for its lifetime. Fails
+    /// loudly rather than crashing when `blob` was tagged with a different V8
+    /// version than the one this crate is linked against.
+    pub fn from_snapshot(blob: &[u8]) -> Result<Self> {
+        if blob.is_empty() {
+            return Err(V8Error::new("snapshot blob was empty"));
+        }
+
+        let (version, raw) = untag_snapshot_blob(blob)
+            .ok_or_else(|| V8Error::new("snapshot blob is malformed or predates version tagging"))?;
+        let linked_version = v8_version();
+        if version != linked_version {
+            return Err(V8Error::new(format!(
+                "snapshot was built for V8 {version}, but this crate is linked against V8 {linked_version}"
+            )));
+        }
+
+        let icu_path = resolve_icu_data_path();
+        initialize_v8(icu_path.as_deref())?;
+
+        let owned = raw.to_vec();
+        let handle = unsafe { shim_create_isolate_from_snapshot(owned.as_ptr(), owned.len()) };
+        if handle.is_null() {
+            return Err(V8Error::new("failed to restore isolate from snapshot"));
+        }
+
+        Ok(Self {
+            handle,
+            snapshot_blob: Some(owned),
+            is_snapshot_creator: false,
+        })
     }
 
     pub fn raw_handle(&self) -> V8IsolateHandle {
         self.handle
     }
 
+    /// A `Clone + Send` handle that can terminate execution on this isolate
+    /// from any thread, independent of the isolate's own lifetime binding to
+    /// its owning thread.
+    pub fn termination_handle(&self) -> IsolateHandle {
+        IsolateHandle::new(self.handle)
+    }
+
     pub fn create_context(&self) -> Result<Context> {
         if self.handle.is_null() {
             return Err(V8Error::new("isolate was disposed"));
@@ -105,6 +306,10 @@ impl Isolate {
             handle,
             isolate: self.handle,
             host_functions: Vec::new(),
+            js_functions: Vec::new(),
+            fast_functions: Vec::new(),
+            module_resolver: None,
+            is_snapshot_creator: self.is_snapshot_creator,
         })
     }
 
@@ -116,6 +321,8 @@ impl Isolate {
             shim_dispose_isolate(self.handle);
         }
         self.handle = ptr::null_mut();
+        // V8 no longer references the startup blob once the isolate is gone.
+        self.snapshot_blob = None;
     }
 }
 
@@ -140,24 +347,39 @@ impl Context {
         }
 
         let c_source = CString::new(source).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
-        let mut result_ptr: *mut c_char = ptr::null_mut();
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut result_ptr: *mut u8 = ptr::null_mut();
+        let mut result_len: usize = 0;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
             shim_context_eval(
                 self.handle,
                 c_source.as_ptr(),
                 &mut result_ptr,
+                &mut result_len,
                 &mut error_ptr,
+                &mut error_len,
             )
         };
 
         if status == 0 {
-            return Err(unsafe { take_error(error_ptr, "V8 evaluation failed") });
+            return Err(unsafe { take_error(error_ptr, error_len, "V8 evaluation failed") });
         }
 
-        let value = unsafe { take_string(result_ptr).unwrap_or_default() };
-        Ok(JsValue::new(value))
+        unsafe { take_value(result_ptr, result_len) }
+    }
+
+    /// Evaluate `source` as [`Context::eval`] does, but abort it if it has not
+    /// finished within `timeout`.
+    ///
+    /// A watchdog thread terminates execution on the deadline; the resulting
+    /// failure is reported as a dedicated timeout [`V8Error`] rather than an
+    /// ordinary exception, so callers can tell a hung `while(true){}` apart
+    /// from a script that simply threw.
+    pub fn eval_with_timeout(&self, source: &str, timeout: Duration) -> Result<JsValue> {
+        let handle = IsolateHandle::new(self.isolate);
+        termination::run_with_timeout(&handle, timeout, || self.eval(source))
     }
 
     pub fn set_global_str(&self, name: &str, value: &str) -> Result<()> {
@@ -166,7 +388,8 @@ impl Context {
         }
         let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
         let c_value = CString::new(value).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
             shim_context_set_global_string(
@@ -174,11 +397,12 @@ impl Context {
                 c_name.as_ptr(),
                 c_value.as_ptr(),
                 &mut error_ptr,
+                &mut error_len,
             )
         };
 
         if status == 0 {
-            return Err(unsafe { take_error(error_ptr, "failed to set global string") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to set global string") });
         }
 
         Ok(())
@@ -190,14 +414,51 @@ impl Context {
         }
 
         let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
-            shim_context_set_global_number(self.handle, c_name.as_ptr(), value, &mut error_ptr)
+            shim_context_set_global_number(self.handle, c_name.as_ptr(), value, &mut error_ptr, &mut error_len)
         };
 
         if status == 0 {
-            return Err(unsafe { take_error(error_ptr, "failed to set global number") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to set global number") });
+        }
+
+        Ok(())
+    }
+
+    /// Set a global to any `Serialize` value, converted through [`JsValue::from_serde`]
+    /// instead of requiring the caller to pick between [`Context::set_global_str`]
+    /// and [`Context::set_global_number`] or hand-roll JSON.
+    pub fn set_global_serde<T: serde::Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+
+        let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let encoded = JsValue::from_serde(value)?.encode();
+        let value_ptr = if encoded.is_empty() {
+            ptr::null()
+        } else {
+            encoded.as_ptr()
+        };
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let status = unsafe {
+            shim_context_set_global_value(
+                self.handle,
+                c_name.as_ptr(),
+                value_ptr,
+                encoded.len(),
+                &mut error_ptr,
+                &mut error_len,
+            )
+        };
+
+        if status == 0 {
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to set global value") });
         }
 
         Ok(())
@@ -210,10 +471,16 @@ impl Context {
         if self.handle.is_null() {
             return Err(V8Error::new("context was disposed"));
         }
+        if self.is_snapshot_creator {
+            return Err(V8Error::new(
+                "cannot register a host function on a snapshot-creator context: function pointers cannot be serialized into the snapshot",
+            ));
+        }
 
         let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
         let function_id = native::register(func);
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
             shim_context_register_host_function(
@@ -221,66 +488,396 @@ impl Context {
                 c_name.as_ptr(),
                 function_id,
                 &mut error_ptr,
+                &mut error_len,
+            )
+        };
+
+        if status == 0 {
+            native::drop_function(function_id);
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to register host function") });
+        }
+
+        self.host_functions.push(function_id);
+        Ok(())
+    }
+
+    /// Register a host function whose arguments and return value are
+    /// converted through serde rather than handled as raw [`JsValue`]s.
+    ///
+    /// `func` receives its arguments decoded into `Args` (typically a tuple or
+    /// a `#[derive(Deserialize)]` struct) via [`JsValue::to_serde`] and its
+    /// `Some(Ret)` return is re-encoded with [`JsValue::from_serde`], so a
+    /// typed host callback doesn't have to match on [`JsValue`] variants by
+    /// hand.
+    pub fn add_function_serde<F, Args, Ret>(&mut self, name: &str, func: F) -> Result<()>
+    where
+        F: Fn(Args) -> Result<Option<Ret>> + Send + Sync + 'static,
+        Args: serde::de::DeserializeOwned,
+        Ret: serde::Serialize,
+    {
+        self.add_function(name, move |args| {
+            let args: Args = JsValue::Array(args.to_vec()).to_serde()?;
+            match func(args)? {
+                Some(value) => Ok(Some(JsValue::from_serde(&value)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// Register an asynchronous host function.
+    ///
+    /// When JS calls `name` the bridge returns a `Promise` immediately and runs
+    /// `func`'s future on a host executor; the `Promise` settles with the
+    /// future's resolved value (or rejects with its error) once it completes,
+    /// so I/O-bound host work no longer blocks the JS thread.
+    pub fn add_async_function<F, Fut>(&mut self, name: &str, func: F) -> Result<()>
+    where
+        F: Fn(Vec<JsValue>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<Option<JsValue>>> + Send + 'static,
+    {
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+        if self.is_snapshot_creator {
+            return Err(V8Error::new(
+                "cannot register a host function on a snapshot-creator context: function pointers cannot be serialized into the snapshot",
+            ));
+        }
+
+        let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let function_id = native::register_async(move |args| Box::pin(func(args)));
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let status = unsafe {
+            shim_context_register_async_function(
+                self.handle,
+                c_name.as_ptr(),
+                function_id,
+                &mut error_ptr,
+                &mut error_len,
             )
         };
 
         if status == 0 {
             native::drop_function(function_id);
-            return Err(unsafe { take_error(error_ptr, "failed to register host function") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to register async function") });
         }
 
         self.host_functions.push(function_id);
         Ok(())
     }
 
+    /// Register an opt-in fast-call host function.
+    ///
+    /// `signature` fixes the scalar parameter and return types; when JS calls
+    /// `name` with matching argument types V8 dispatches straight through
+    /// [`fast::pacm_v8__fast_invoke`] with the scalars passed by value, skipping
+    /// the argument-buffer encoding and allocation of the ordinary path. Calls whose
+    /// runtime types don't match the declared signature fall back to the slow
+    /// path, so a slow twin stays available.
+    pub fn add_fast_function<F>(
+        &mut self,
+        name: &str,
+        signature: FastSignature,
+        func: F,
+    ) -> Result<()>
+    where
+        F: Fn(&[FastValue]) -> Result<FastValue> + Send + Sync + 'static,
+    {
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+        if self.is_snapshot_creator {
+            return Err(V8Error::new(
+                "cannot register a host function on a snapshot-creator context: function pointers cannot be serialized into the snapshot",
+            ));
+        }
+
+        let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let params: Vec<u8> = signature.params.iter().map(|ty| ty.wire_byte()).collect();
+        let return_type = signature.ret.wire_byte();
+        let function_id = fast::register(signature, func);
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let params_ptr = if params.is_empty() {
+            ptr::null()
+        } else {
+            params.as_ptr()
+        };
+
+        let status = unsafe {
+            shim_context_register_fast_function(
+                self.handle,
+                c_name.as_ptr(),
+                function_id,
+                params_ptr,
+                params.len(),
+                return_type,
+                &mut error_ptr,
+                &mut error_len,
+            )
+        };
+
+        if status == 0 {
+            fast::drop_function(function_id);
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to register fast function") });
+        }
+
+        self.fast_functions.push(function_id);
+        Ok(())
+    }
+
+    /// Register the callback that supplies source text for `import` specifiers
+    /// encountered by [`Context::eval_module`].
+    ///
+    /// The resolver receives the requested specifier and its referrer and
+    /// returns the module source. Registering a new resolver replaces (and
+    /// releases) any previous one.
+    pub fn set_module_resolver<F>(&mut self, resolver: F)
+    where
+        F: Fn(&str, &str) -> Result<String> + Send + Sync + 'static,
+    {
+        if let Some(previous) = self.module_resolver.take() {
+            module::drop_resolver(previous);
+        }
+        self.module_resolver = Some(module::register(resolver));
+    }
+
+    /// Compile and evaluate `source` as an ES module named `specifier`.
+    ///
+    /// Import dependencies are resolved through the callback registered with
+    /// [`Context::set_module_resolver`]; the whole graph is instantiated and
+    /// evaluated, and the resolved module namespace object is returned. A
+    /// top-level `await` is driven to completion before this returns.
+    pub fn eval_module(&self, specifier: &str, source: &str) -> Result<JsValue> {
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+
+        let c_specifier = CString::new(specifier).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let c_source = CString::new(source).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let mut result_ptr: *mut u8 = ptr::null_mut();
+        let mut result_len: usize = 0;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let status = unsafe {
+            shim_context_eval_module(
+                self.handle,
+                c_specifier.as_ptr(),
+                c_source.as_ptr(),
+                self.module_resolver.unwrap_or(0),
+                &mut result_ptr,
+                &mut result_len,
+                &mut error_ptr,
+                &mut error_len,
+            )
+        };
+
+        if status == 0 {
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to evaluate module") });
+        }
+
+        unsafe { take_value(result_ptr, result_len) }
+    }
+
+    /// Load and evaluate an ES module from only its entry specifier.
+    ///
+    /// The entry source is fetched through the resolver registered with
+    /// [`Context::set_module_resolver`], as if it were itself an import with no
+    /// referrer, so the caller need not read the entry file before handing it
+    /// to the module graph. Nested imports resolve exactly as in
+    /// [`Context::eval_module`], sharing the resolver's per-specifier source
+    /// cache; no circular-import check is made on the Rust side.
+    pub fn eval_module_entry(&self, entry_specifier: &str) -> Result<JsValue> {
+        let resolver_id = self
+            .module_resolver
+            .ok_or_else(|| V8Error::new("no module resolver registered"))?;
+        let source = module::resolve_entry(resolver_id, entry_specifier)?;
+        self.eval_module(entry_specifier, &source)
+    }
+
+    /// Acquire a [`ThreadsafeFunction`] handle for the global function `name`.
+    ///
+    /// The returned handle is `Clone + Send` and may be called from any thread;
+    /// each call is marshaled back onto this isolate's task queue, which the JS
+    /// thread must drain with [`Context::pump`].
+    pub fn threadsafe_function(&mut self, name: &str) -> Result<ThreadsafeFunction> {
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+
+        let c_name = CString::new(name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let func_ref =
+            unsafe { shim_context_acquire_function(self.handle, c_name.as_ptr(), &mut error_ptr, &mut error_len) };
+        if func_ref == 0 {
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to acquire JS function") });
+        }
+
+        self.js_functions.push(func_ref);
+        Ok(ThreadsafeFunction::new(self.isolate, self.handle, func_ref))
+    }
+
+    /// Drain queued [`ThreadsafeFunction`] calls, executing each on this (the
+    /// JS) thread. Call from the isolate's owning thread whenever background
+    /// work may have enqueued invocations.
+    pub fn pump(&self) {
+        if self.handle.is_null() {
+            return;
+        }
+        threadsafe::pump(self.isolate);
+    }
+
+    /// Run V8's microtask queue to completion.
+    ///
+    /// `eval`/`call_function` only run the synchronous portion of a script;
+    /// any `.then` reaction or resumed `async` function is scheduled as a
+    /// microtask and needs this (or [`Context::resolve_promise`], which calls
+    /// it internally) to actually execute.
+    pub fn run_microtasks(&self) {
+        if self.handle.is_null() {
+            return;
+        }
+        unsafe { shim_context_run_microtasks(self.handle) };
+    }
+
+    /// Drive `value` to completion if it is a [`JsValue::Promise`], returning
+    /// its fulfillment value or a [`V8Error`] carrying its rejection message.
+    /// Non-promise values pass through unchanged.
+    ///
+    /// Repeatedly pumps queued [`ThreadsafeFunction`] calls and the microtask
+    /// queue so both host-driven and purely synchronous promises can settle,
+    /// checking the promise's state after each pass until it settles or
+    /// `timeout` elapses.
+    pub fn resolve_promise(&self, value: &JsValue, timeout: Duration) -> Result<JsValue> {
+        let promise_ref = match value {
+            JsValue::Promise(promise_ref) => *promise_ref,
+            other => return Ok(other.clone()),
+        };
+        if self.handle.is_null() {
+            return Err(V8Error::new("context was disposed"));
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.pump();
+            self.run_microtasks();
+
+            let mut value_ptr: *mut u8 = ptr::null_mut();
+            let mut value_len: usize = 0;
+            let state = unsafe { shim_promise_state(self.handle, promise_ref, &mut value_ptr, &mut value_len) };
+
+            match state {
+                1 => return unsafe { take_value(value_ptr, value_len) },
+                2 => {
+                    let rejection = unsafe { take_value(value_ptr, value_len) }?;
+                    return Err(V8Error::new(rejection.rejection_message()));
+                }
+                _ => {
+                    if Instant::now() >= deadline {
+                        return Err(V8Error::new(format!(
+                            "promise did not settle within {}ms",
+                            timeout.as_millis()
+                        )));
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+    }
+
     pub fn call_function(&self, fn_name: &str, args: &[&str]) -> Result<JsValue> {
+        let args_value =
+            JsValue::Array(args.iter().map(|value| JsValue::new((*value).to_string())).collect());
+        self.call_function_value(fn_name, &args_value)
+    }
+
+    /// Call `fn_name` with any `Serialize` argument list, converted through
+    /// [`JsValue::from_serde`] and decoded back into `Ret` through
+    /// [`JsValue::to_serde`], so callers don't have to flatten structured
+    /// arguments into `&[&str]` or re-parse the JSON result themselves.
+    ///
+    /// `Args` should serialize to a JSON array (the call's argument list); a
+    /// value that serializes to anything else is passed as a single argument.
+    pub fn call_function_serde<Args, Ret>(&self, fn_name: &str, args: &Args) -> Result<Ret>
+    where
+        Args: serde::Serialize,
+        Ret: serde::de::DeserializeOwned,
+    {
+        let args_value = match JsValue::from_serde(args)? {
+            JsValue::Array(values) => JsValue::Array(values),
+            other => JsValue::Array(vec![other]),
+        };
+        self.call_function_value(fn_name, &args_value)?.to_serde()
+    }
+
+    /// Shared tail of [`Context::call_function`]/[`Context::call_function_serde`]:
+    /// encode `args_value` (already an array) and invoke the shim.
+    fn call_function_value(&self, fn_name: &str, args_value: &JsValue) -> Result<JsValue> {
         if self.handle.is_null() {
             return Err(V8Error::new("context was disposed"));
         }
 
         let c_name = CString::new(fn_name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
-        let arg_cstrings: Result<Vec<CString>> = args
-            .iter()
-            .map(|value| CString::new(*value).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE)))
-            .collect();
-        let arg_cstrings = arg_cstrings?;
-        let arg_ptrs: Vec<*const c_char> =
-            arg_cstrings.iter().map(|value| value.as_ptr()).collect();
-        let arg_ptr = if arg_ptrs.is_empty() {
+        let encoded = args_value.encode();
+        let args_ptr = if encoded.is_empty() {
             ptr::null()
         } else {
-            arg_ptrs.as_ptr()
+            encoded.as_ptr()
         };
 
-        let mut result_ptr: *mut c_char = ptr::null_mut();
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut result_ptr: *mut u8 = ptr::null_mut();
+        let mut result_len: usize = 0;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
             shim_context_call_function(
                 self.handle,
                 c_name.as_ptr(),
-                arg_ptr,
-                arg_ptrs.len(),
+                args_ptr,
+                encoded.len(),
                 &mut result_ptr,
+                &mut result_len,
                 &mut error_ptr,
+                &mut error_len,
             )
         };
 
         if status == 0 {
-            return Err(unsafe { take_error(error_ptr, "failed to call function") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to call function") });
         }
 
-        let value = unsafe { take_string(result_ptr).unwrap_or_default() };
-        Ok(JsValue::new(value))
+        unsafe { take_value(result_ptr, result_len) }
     }
 
     pub fn dispose(&mut self) {
         if !self.host_functions.is_empty() {
             native::drop_many(self.host_functions.drain(..));
         }
+        for id in self.fast_functions.drain(..) {
+            fast::drop_function(id);
+        }
+        if let Some(resolver) = self.module_resolver.take() {
+            module::drop_resolver(resolver);
+        }
         if self.handle.is_null() {
+            self.js_functions.clear();
             return;
         }
+        // Any async host call still running for this context must not be
+        // allowed to settle it later through a now-dangling pointer.
+        native::abandon_context(self.handle);
+        for func_ref in self.js_functions.drain(..) {
+            unsafe {
+                shim_context_release_function(self.handle, func_ref);
+            }
+        }
         unsafe {
             shim_dispose_context(self.handle);
         }
@@ -301,13 +898,48 @@ impl Script {
         }
 
         let c_source = CString::new(source).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let handle =
-            unsafe { shim_compile_script(isolate.handle, c_source.as_ptr(), &mut error_ptr) };
+            unsafe { shim_compile_script(isolate.handle, c_source.as_ptr(), &mut error_ptr, &mut error_len) };
+
+        if handle.is_null() {
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to compile script") });
+        }
+
+        Ok(Self {
+            handle,
+            isolate: isolate.handle,
+        })
+    }
+
+    /// Compile `source` as [`Script::compile`] does, naming it
+    /// `resource_name` so exceptions and stack frames raised while running it
+    /// report that name instead of an anonymous script.
+    pub fn compile_named(isolate: &Isolate, source: &str, resource_name: &str) -> Result<Self> {
+        if isolate.handle.is_null() {
+            return Err(V8Error::new("isolate was disposed"));
+        }
+
+        let c_source = CString::new(source).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let c_resource_name =
+            CString::new(resource_name).map_err(|_| V8Error::new(NULL_BYTE_MESSAGE))?;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
+
+        let handle = unsafe {
+            shim_compile_script_named(
+                isolate.handle,
+                c_source.as_ptr(),
+                c_resource_name.as_ptr(),
+                &mut error_ptr,
+                &mut error_len,
+            )
+        };
 
         if handle.is_null() {
-            return Err(unsafe { take_error(error_ptr, "failed to compile script") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to compile script") });
         }
 
         Ok(Self {
@@ -333,19 +965,35 @@ impl Script {
             ));
         }
 
-        let mut result_ptr: *mut c_char = ptr::null_mut();
-        let mut error_ptr: *mut c_char = ptr::null_mut();
+        let mut result_ptr: *mut u8 = ptr::null_mut();
+        let mut result_len: usize = 0;
+        let mut error_ptr: *mut u8 = ptr::null_mut();
+        let mut error_len: usize = 0;
 
         let status = unsafe {
-            shim_script_run(self.handle, context.handle, &mut result_ptr, &mut error_ptr)
+            shim_script_run(
+                self.handle,
+                context.handle,
+                &mut result_ptr,
+                &mut result_len,
+                &mut error_ptr,
+                &mut error_len,
+            )
         };
 
         if status == 0 {
-            return Err(unsafe { take_error(error_ptr, "failed to run script") });
+            return Err(unsafe { take_error(error_ptr, error_len, "failed to run script") });
         }
 
-        let value = unsafe { take_string(result_ptr).unwrap_or_default() };
-        Ok(JsValue::new(value))
+        unsafe { take_value(result_ptr, result_len) }
+    }
+
+    /// Run this script as [`Script::run`] does, but abort it if it has not
+    /// finished within `timeout`. See [`Context::eval_with_timeout`] for how
+    /// the timeout failure is reported.
+    pub fn run_with_timeout(&self, context: &Context, timeout: Duration) -> Result<JsValue> {
+        let handle = IsolateHandle::new(self.isolate);
+        termination::run_with_timeout(&handle, timeout, || self.run(context))
     }
 
     pub fn dispose(&mut self) {
@@ -364,3 +1012,40 @@ impl Drop for Script {
         self.dispose();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{tag_snapshot_blob_with_version, untag_snapshot_blob};
+
+    #[test]
+    fn snapshot_tag_round_trips() {
+        let raw = vec![1, 2, 3, 4, 5];
+        let tagged = tag_snapshot_blob_with_version("12.3.219.9", raw.clone());
+        let (version, untagged) = untag_snapshot_blob(&tagged).expect("tagged blob should parse");
+        assert_eq!(version, "12.3.219.9");
+        assert_eq!(untagged, raw.as_slice());
+    }
+
+    #[test]
+    fn snapshot_tag_round_trips_with_empty_blob() {
+        let tagged = tag_snapshot_blob_with_version("13.0.0.0", Vec::new());
+        let (version, untagged) = untag_snapshot_blob(&tagged).expect("tagged blob should parse");
+        assert_eq!(version, "13.0.0.0");
+        assert!(untagged.is_empty());
+    }
+
+    #[test]
+    fn untag_snapshot_blob_rejects_truncated_header() {
+        assert!(untag_snapshot_blob(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn untag_snapshot_blob_rejects_truncated_version() {
+        // Claims a 10-byte version string but the buffer only holds 2 bytes
+        // of it — e.g. a pre-tagging blob whose first four bytes happen to
+        // look like a length prefix.
+        let mut blob = 10u32.to_le_bytes().to_vec();
+        blob.extend_from_slice(b"ab");
+        assert!(untag_snapshot_blob(&blob).is_none());
+    }
+}
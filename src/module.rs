@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::error::{Result, V8Error};
+
+/// A host callback that supplies the source text for an `import` specifier.
+///
+/// It receives the requested specifier and the referrer (the module issuing the
+/// `import`) and returns the resolved module source.
+type ModuleResolver = dyn Fn(&str, &str) -> Result<String> + Send + Sync + 'static;
+
+/// Per-registration state layered on top of the raw resolver callback: a cache
+/// of already-loaded sources keyed by specifier, so a module imported from
+/// several referrers is only fetched once.
+///
+/// This is also what makes *legal* circular imports (`a.js` imports `b.js`
+/// imports `a.js`) work transparently: V8 itself dedups its module graph by
+/// resolved specifier and never calls back into `resolve` twice for the same
+/// one, so by the time a cycle closes the specifier is already served from
+/// this cache rather than re-invoking the host resolver. There is no
+/// in-Rust recursion to guard against — each `pacm_v8__resolve_module` call
+/// from the shim runs to completion before the next one starts — so there is
+/// no reachable "currently resolving" state to detect a cycle from on this
+/// side.
+struct Registration {
+    resolver: Arc<ModuleResolver>,
+    cache: Mutex<HashMap<String, String>>,
+}
+
+static RESOLVERS: OnceLock<Mutex<HashMap<u64, Arc<Registration>>>> = OnceLock::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn resolvers() -> &'static Mutex<HashMap<u64, Arc<Registration>>> {
+    RESOLVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn register<F>(resolver: F) -> u64
+where
+    F: Fn(&str, &str) -> Result<String> + Send + Sync + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    resolvers().lock().unwrap().insert(
+        id,
+        Arc::new(Registration {
+            resolver: Arc::from(Box::new(resolver) as Box<ModuleResolver>),
+            cache: Mutex::new(HashMap::new()),
+        }),
+    );
+    id
+}
+
+pub(crate) fn drop_resolver(id: u64) {
+    if let Some(lock) = RESOLVERS.get() {
+        lock.lock().unwrap().remove(&id);
+    }
+}
+
+/// Strip a leading UTF-8 byte-order mark, which some module sources carry but
+/// which V8's parser does not expect as script text.
+fn strip_bom(source: String) -> String {
+    source
+        .strip_prefix('\u{feff}')
+        .map(str::to_string)
+        .unwrap_or(source)
+}
+
+fn resolve(id: u64, specifier: &str, referrer: &str) -> Result<String> {
+    let registration = {
+        let guard = resolvers().lock().unwrap();
+        guard
+            .get(&id)
+            .map(Arc::clone)
+            .ok_or_else(|| V8Error::new("module resolver not found"))?
+    };
+
+    if let Some(cached) = registration.cache.lock().unwrap().get(specifier) {
+        return Ok(cached.clone());
+    }
+
+    let outcome = (registration.resolver)(specifier, referrer).map_err(|err| {
+        V8Error::new(format!(
+            "failed to resolve module \"{specifier}\" (imported from \"{referrer}\"): {err}"
+        ))
+    });
+
+    let source = strip_bom(outcome?);
+    registration
+        .cache
+        .lock()
+        .unwrap()
+        .insert(specifier.to_string(), source.clone());
+    Ok(source)
+}
+
+/// Resolve the entry module's source via the registered resolver, as if it
+/// were imported from an empty (no referrer) module. Lets
+/// [`crate::Context::eval_module_entry`] load the root module the same way as
+/// every nested import instead of requiring the caller to supply its source.
+pub(crate) fn resolve_entry(id: u64, specifier: &str) -> Result<String> {
+    resolve(id, specifier, "")
+}
+
+/// Invoked by the shim's module-resolve callback. Looks up the registered
+/// resolver, calls it, and hands back the source as a UTF-8 buffer the host
+/// releases via `pacm_v8__buffer_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__resolve_module(
+    resolver_id: u64,
+    specifier: *const c_char,
+    referrer: *const c_char,
+    source_out: *mut *mut u8,
+    source_len_out: *mut usize,
+    error_out: *mut *mut u8,
+    error_len_out: *mut usize,
+) -> i32 {
+    if !source_out.is_null() {
+        unsafe {
+            *source_out = ptr::null_mut();
+        }
+    }
+    if !source_len_out.is_null() {
+        unsafe {
+            *source_len_out = 0;
+        }
+    }
+    if !error_out.is_null() {
+        unsafe {
+            *error_out = ptr::null_mut();
+        }
+    }
+    if !error_len_out.is_null() {
+        unsafe {
+            *error_len_out = 0;
+        }
+    }
+
+    let specifier = unsafe { cstr_to_string(specifier) };
+    let referrer = unsafe { cstr_to_string(referrer) };
+
+    match resolve(resolver_id, &specifier, &referrer) {
+        Ok(source) => {
+            let mut bytes = source.into_bytes().into_boxed_slice();
+            let ptr = bytes.as_mut_ptr();
+            let len = bytes.len();
+            std::mem::forget(bytes);
+            if !source_out.is_null() {
+                unsafe {
+                    *source_out = ptr;
+                }
+            }
+            if !source_len_out.is_null() {
+                unsafe {
+                    *source_len_out = len;
+                }
+            }
+            1
+        }
+        Err(error) => {
+            let mut encoded = error.encode().into_boxed_slice();
+            let ptr = encoded.as_mut_ptr();
+            let len = encoded.len();
+            std::mem::forget(encoded);
+            if !error_out.is_null() {
+                unsafe {
+                    *error_out = ptr;
+                }
+            }
+            if !error_len_out.is_null() {
+                unsafe {
+                    *error_len_out = len;
+                }
+            }
+            0
+        }
+    }
+}
+
+unsafe fn cstr_to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
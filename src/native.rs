@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 use std::os::raw::c_char;
 use std::ptr;
 use std::slice;
@@ -7,29 +7,58 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
 use crate::error::{Result, V8Error};
+use crate::executor::{self, BoxFuture};
+use crate::ffi::{self, V8ContextHandle};
 use crate::value::JsValue;
 
 type HostCallback = dyn Fn(&[JsValue]) -> Result<Option<JsValue>> + Send + Sync + 'static;
+type AsyncHostCallback =
+    dyn Fn(Vec<JsValue>) -> BoxFuture<Result<Option<JsValue>>> + Send + Sync + 'static;
 
-struct HostFunctionEntry {
-    callback: Arc<HostCallback>,
+/// A registered host function. Synchronous entries resolve inline on the JS
+/// thread; async entries yield a future driven on a host-executor thread and
+/// settle a JS Promise when it completes.
+enum HostFunctionEntry {
+    Sync(Arc<HostCallback>),
+    Async(Arc<AsyncHostCallback>),
+}
+
+/// A JS Promise awaiting settlement from a still-running async callback. The
+/// context handle is kept so the completion can reach back into the owning
+/// isolate to resolve or reject.
+struct PendingPromise {
+    context: usize,
 }
 
 static REGISTRY: OnceLock<Mutex<HashMap<u64, HostFunctionEntry>>> = OnceLock::new();
+static PENDING: OnceLock<Mutex<HashMap<u64, PendingPromise>>> = OnceLock::new();
 static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
 fn registry() -> &'static Mutex<HashMap<u64, HostFunctionEntry>> {
     REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+fn pending() -> &'static Mutex<HashMap<u64, PendingPromise>> {
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 pub(crate) fn register<F>(callback: F) -> u64
 where
     F: Fn(&[JsValue]) -> Result<Option<JsValue>> + Send + Sync + 'static,
 {
     let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
-    let entry = HostFunctionEntry {
-        callback: Arc::from(Box::new(callback) as Box<HostCallback>),
-    };
+    let entry = HostFunctionEntry::Sync(Arc::from(Box::new(callback) as Box<HostCallback>));
+    let mut guard = registry().lock().unwrap();
+    guard.insert(id, entry);
+    id
+}
+
+pub(crate) fn register_async<F>(callback: F) -> u64
+where
+    F: Fn(Vec<JsValue>) -> BoxFuture<Result<Option<JsValue>>> + Send + Sync + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let entry = HostFunctionEntry::Async(Arc::from(Box::new(callback) as Box<AsyncHostCallback>));
     let mut guard = registry().lock().unwrap();
     guard.insert(id, entry);
     id
@@ -49,120 +78,280 @@ pub(crate) fn drop_many(ids: impl IntoIterator<Item = u64>) {
     }
 }
 
+/// Abandon every pending async-call Promise still tracked for `context`.
+///
+/// Call this before the context is disposed: `resolve`/`reject` settle a
+/// pending Promise by calling back into its owning context, so an async
+/// callback that completes after the context is gone would otherwise settle
+/// a freed context through a stale pointer. Dropping the entry here instead
+/// makes that eventual `resolve`/`reject` a silent no-op (its `PENDING`
+/// lookup just misses), at the cost of never hearing a settlement for
+/// outstanding async host calls that outlive their context.
+pub(crate) fn abandon_context(context: V8ContextHandle) {
+    let target = context as usize;
+    if let Some(lock) = PENDING.get() {
+        lock.lock().unwrap().retain(|_, promise| promise.context != target);
+    }
+}
+
 fn invoke(id: u64, args: &[JsValue]) -> Result<Option<JsValue>> {
     let callback = {
         let guard = registry().lock().unwrap();
-        guard
-            .get(&id)
-            .map(|entry| Arc::clone(&entry.callback))
-            .ok_or_else(|| V8Error::new("native function not found"))?
+        match guard.get(&id) {
+            Some(HostFunctionEntry::Sync(callback)) => Arc::clone(callback),
+            Some(HostFunctionEntry::Async(_)) => {
+                return Err(V8Error::new(
+                    "async host function invoked through the synchronous path",
+                ));
+            }
+            None => return Err(V8Error::new("native function not found")),
+        }
     };
     (callback)(args)
 }
 
-unsafe fn convert_args(args: *const *const c_char, count: usize) -> Result<Vec<JsValue>> {
-    if args.is_null() || count == 0 {
-        return Ok(Vec::new());
+/// Drive an async host callback to completion on a dedicated executor thread
+/// and settle the owning JS Promise identified by `resolver_id`.
+fn spawn_async(id: u64, resolver_id: u64, context: V8ContextHandle, args: Vec<JsValue>) {
+    let callback = {
+        let guard = registry().lock().unwrap();
+        match guard.get(&id) {
+            Some(HostFunctionEntry::Async(callback)) => Some(Arc::clone(callback)),
+            _ => None,
+        }
+    };
+
+    {
+        let mut guard = pending().lock().unwrap();
+        guard.insert(
+            resolver_id,
+            PendingPromise {
+                context: context as usize,
+            },
+        );
     }
 
-    let arg_slice = unsafe { slice::from_raw_parts(args, count) };
-    let mut values = Vec::with_capacity(count);
+    let Some(callback) = callback else {
+        reject(resolver_id, &V8Error::new("async function not found"));
+        return;
+    };
 
-    for &ptr in arg_slice {
-        if ptr.is_null() {
-            values.push(JsValue::new(String::new()));
-            continue;
+    std::thread::spawn(move || {
+        let future = callback(args);
+        match executor::block_on(future) {
+            Ok(value) => resolve(resolver_id, value.unwrap_or(JsValue::Null)),
+            Err(error) => reject(resolver_id, &error),
         }
+    });
+}
+
+/// Hand a resolved value back into the owning isolate, settling its Promise.
+fn resolve(resolver_id: u64, value: JsValue) {
+    let Some(promise) = pending().lock().unwrap().remove(&resolver_id) else {
+        return;
+    };
+    let encoded = value.encode();
+    settle(promise.context, resolver_id, 1, &encoded);
+}
 
-        let value = unsafe { CStr::from_ptr(ptr) }
-            .to_string_lossy()
-            .into_owned();
-        values.push(JsValue::new(value));
+/// Reject the Promise associated with `resolver_id`, carrying `error`'s message.
+fn reject(resolver_id: u64, error: &V8Error) {
+    let Some(promise) = pending().lock().unwrap().remove(&resolver_id) else {
+        return;
+    };
+    settle(promise.context, resolver_id, 0, error.message().as_bytes());
+}
+
+/// Forward a settlement onto the owning isolate, discarding any shim-side error
+/// buffer it returns.
+fn settle(context: usize, resolver_id: u64, is_ok: i32, payload: &[u8]) {
+    let mut error_ptr: *mut u8 = ptr::null_mut();
+    let mut error_len: usize = 0;
+    unsafe {
+        ffi::shim_settle_promise(
+            context as V8ContextHandle,
+            resolver_id,
+            is_ok,
+            payload.as_ptr(),
+            payload.len(),
+            &mut error_ptr,
+            &mut error_len,
+        );
+    }
+    if !error_ptr.is_null() {
+        unsafe { ffi::shim_free_buffer(error_ptr, error_len) };
+    }
+}
+
+/// Decode the encoded argument buffer (an [`JsValue::Array`]) handed to a host
+/// callback into the typed arguments the closure expects.
+unsafe fn convert_args(args: *const u8, len: usize) -> Result<Vec<JsValue>> {
+    if args.is_null() || len == 0 {
+        return Ok(Vec::new());
     }
 
-    Ok(values)
+    let bytes = unsafe { slice::from_raw_parts(args, len) };
+    match JsValue::decode(bytes)? {
+        JsValue::Array(values) => Ok(values),
+        other => Ok(vec![other]),
+    }
 }
 
-unsafe fn set_string(out: *mut *mut c_char, value: Option<String>) -> Result<()> {
-    if out.is_null() {
-        return Ok(());
+/// Encode `value` into a freshly allocated buffer owned by the host, writing the
+/// pointer and length into the out-params. The host releases it via
+/// [`pacm_v8__buffer_free`].
+unsafe fn emit_buffer(out: *mut *mut u8, len_out: *mut usize, value: &JsValue) {
+    let mut encoded = value.encode().into_boxed_slice();
+    let ptr = encoded.as_mut_ptr();
+    let len = encoded.len();
+    std::mem::forget(encoded);
+    if !out.is_null() {
+        unsafe {
+            *out = ptr;
+        }
+    }
+    if !len_out.is_null() {
+        unsafe {
+            *len_out = len;
+        }
     }
+}
 
-    unsafe {
-        *out = ptr::null_mut();
+/// Encode a [`V8Error`] into a host-owned buffer so the shim can throw the
+/// matching JS error constructor. Released by the host via
+/// [`pacm_v8__buffer_free`].
+unsafe fn emit_error(out: *mut *mut u8, len_out: *mut usize, error: &V8Error) {
+    let mut encoded = error.encode().into_boxed_slice();
+    let ptr = encoded.as_mut_ptr();
+    let len = encoded.len();
+    std::mem::forget(encoded);
+    if !out.is_null() {
+        unsafe {
+            *out = ptr;
+        }
     }
-    if let Some(value) = value {
-        let cstring =
-            CString::new(value).map_err(|_| V8Error::new("string contained interior null byte"))?;
+    if !len_out.is_null() {
         unsafe {
-            *out = cstring.into_raw();
+            *len_out = len;
         }
     }
-    Ok(())
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn pacm_v8__host_function_invoke(
     id: u64,
-    args: *const *const c_char,
-    arg_count: usize,
-    result_out: *mut *mut c_char,
-    error_out: *mut *mut c_char,
+    args: *const u8,
+    args_len: usize,
+    result_out: *mut *mut u8,
+    result_len_out: *mut usize,
+    error_out: *mut *mut u8,
+    error_len_out: *mut usize,
 ) -> i32 {
     if !result_out.is_null() {
         unsafe {
             *result_out = ptr::null_mut();
         }
     }
+    if !result_len_out.is_null() {
+        unsafe {
+            *result_len_out = 0;
+        }
+    }
     if !error_out.is_null() {
         unsafe {
             *error_out = ptr::null_mut();
         }
     }
+    if !error_len_out.is_null() {
+        unsafe {
+            *error_len_out = 0;
+        }
+    }
 
-    let args = match unsafe { convert_args(args, arg_count) } {
+    let args = match unsafe { convert_args(args, args_len) } {
         Ok(values) => values,
         Err(error) => {
-            if !error_out.is_null() {
-                let message = CString::new(error.message())
-                    .unwrap_or_else(|_| CString::new("failed to convert arguments").unwrap());
-                unsafe {
-                    *error_out = message.into_raw();
-                }
-            }
+            unsafe { emit_error(error_out, error_len_out, &error) };
             return 0;
         }
     };
 
     match invoke(id, &args) {
-        Ok(Some(value)) => match unsafe { set_string(result_out, Some(value.into_string())) } {
-            Ok(_) => 1,
-            Err(error) => {
-                if !error_out.is_null() {
-                    let message = CString::new(error.message()).unwrap_or_else(|_| {
-                        CString::new("host function result contained interior null byte").unwrap()
-                    });
-                    unsafe {
-                        *error_out = message.into_raw();
-                    }
-                }
-                0
-            }
-        },
+        Ok(Some(value)) => {
+            unsafe { emit_buffer(result_out, result_len_out, &value) };
+            1
+        }
         Ok(None) => 1,
         Err(error) => {
-            if !error_out.is_null() {
-                let message = CString::new(error.message())
-                    .unwrap_or_else(|_| CString::new("host function failed").unwrap());
-                unsafe {
-                    *error_out = message.into_raw();
-                }
-            }
+            unsafe { emit_error(error_out, error_len_out, &error) };
             0
         }
     }
 }
 
+/// Invoked by the shim when JS calls an async host function. The arguments are
+/// decoded, the future is spawned on the host executor, and control returns to
+/// JS immediately with the Promise the shim already created for `resolver_id`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__host_function_invoke_async(
+    id: u64,
+    args: *const u8,
+    args_len: usize,
+    context: V8ContextHandle,
+    resolver_id: u64,
+) -> i32 {
+    let args = match unsafe { convert_args(args, args_len) } {
+        Ok(values) => values,
+        Err(_) => Vec::new(),
+    };
+    spawn_async(id, resolver_id, context, args);
+    1
+}
+
+/// Settle a pending Promise with a successful encoded value. Exposed so an
+/// embedder driving its own executor can resolve from the completion callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__resolve_promise(
+    resolver_id: u64,
+    value: *const u8,
+    value_len: usize,
+) {
+    let decoded = if value.is_null() || value_len == 0 {
+        JsValue::Null
+    } else {
+        let bytes = unsafe { slice::from_raw_parts(value, value_len) };
+        JsValue::decode(bytes).unwrap_or(JsValue::Null)
+    };
+    resolve(resolver_id, decoded);
+}
+
+/// Reject a pending Promise with a UTF-8 error message.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__reject_promise(
+    resolver_id: u64,
+    message: *const u8,
+    message_len: usize,
+) {
+    let text = if message.is_null() || message_len == 0 {
+        "async host function rejected".to_string()
+    } else {
+        let bytes = unsafe { slice::from_raw_parts(message, message_len) };
+        String::from_utf8_lossy(bytes).into_owned()
+    };
+    reject(resolver_id, &V8Error::new(text));
+}
+
+/// Release an encoded value buffer previously handed to the host by a host
+/// callback (see [`pacm_v8__host_function_invoke`]).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pacm_v8__buffer_free(ptr: *mut u8, len: usize) {
+    if ptr.is_null() || len == 0 {
+        return;
+    }
+    let slice = unsafe { slice::from_raw_parts_mut(ptr, len) };
+    drop(unsafe { Box::from_raw(slice) });
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn pacm_v8__host_function_drop(id: u64) {
     drop_function(id);
@@ -1,23 +1,65 @@
-use std::ffi::CStr;
-use std::os::raw::c_char;
+use std::slice;
 
-use crate::error::V8Error;
+use crate::error::{Result, V8Error};
 use crate::ffi;
+use crate::value::JsValue;
 
-pub(crate) unsafe fn take_string(ptr: *mut c_char) -> Option<String> {
-    if ptr.is_null() {
-        return None;
+/// Decode an encoded [`V8Error`] structure handed back by the shim and release
+/// its backing allocation through `shim_free_buffer`.
+///
+/// A null/empty buffer yields a plain error carrying `fallback`.
+pub(crate) unsafe fn take_error(ptr: *mut u8, len: usize, fallback: &str) -> V8Error {
+    if ptr.is_null() || len == 0 {
+        return V8Error::new(fallback);
     }
-    let string = unsafe { CStr::from_ptr(ptr) }
-        .to_string_lossy()
-        .into_owned();
+    let error = {
+        let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+        V8Error::decode(bytes, fallback)
+    };
     unsafe {
-        ffi::shim_free_string(ptr);
+        ffi::shim_free_buffer(ptr, len);
     }
-    Some(string)
+    error
 }
 
-pub(crate) unsafe fn take_error(ptr: *mut c_char, fallback: &str) -> V8Error {
-    let message = unsafe { take_string(ptr) }.unwrap_or_else(|| fallback.to_string());
-    V8Error::new(message)
+/// Copy a raw byte buffer handed back by the shim into an owned `Vec` and
+/// release its backing allocation through `shim_free_buffer`.
+///
+/// Unlike [`take_value`], the bytes are taken verbatim — used for opaque
+/// payloads such as startup snapshot blobs that are not TLV-encoded values.
+pub(crate) unsafe fn take_bytes(ptr: *mut u8, len: usize) -> Vec<u8> {
+    if ptr.is_null() || len == 0 {
+        return Vec::new();
+    }
+    let bytes = {
+        let slice = unsafe { slice::from_raw_parts(ptr, len) };
+        slice.to_vec()
+    };
+    unsafe {
+        ffi::shim_free_buffer(ptr, len);
+    }
+    bytes
+}
+
+/// Decode an encoded [`JsValue`] buffer handed back by the shim and release its
+/// backing allocation through `shim_free_buffer`.
+///
+/// A null pointer or zero length decodes to [`JsValue::Null`], matching a JS
+/// call that produced `undefined`/`null`.
+pub(crate) unsafe fn take_value(ptr: *mut u8, len: usize) -> Result<JsValue> {
+    if ptr.is_null() || len == 0 {
+        if !ptr.is_null() {
+            unsafe { ffi::shim_free_buffer(ptr, len) };
+        }
+        return Ok(JsValue::Null);
+    }
+
+    let value = {
+        let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+        JsValue::decode(bytes)
+    };
+    unsafe {
+        ffi::shim_free_buffer(ptr, len);
+    }
+    value
 }
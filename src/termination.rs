@@ -0,0 +1,94 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{Result, V8Error};
+use crate::ffi::{self, V8IsolateHandle};
+
+/// A `Clone + Send` handle that can cooperatively stop a runaway script from
+/// any thread, mirroring V8's own `TerminateExecution`/
+/// `CancelTerminateExecution` on the isolate's thread-safe handle.
+///
+/// Obtained from [`crate::Isolate::termination_handle`]. Like
+/// [`crate::ThreadsafeFunction`], it only ferries the isolate's address across
+/// threads; the underlying calls are safe to make concurrently with running
+/// script because V8 implements them without touching the isolate's heap.
+#[derive(Clone)]
+pub struct IsolateHandle {
+    isolate: usize,
+}
+
+// The raw handle is only ever passed back into the shim's threadsafe
+// termination calls, never dereferenced directly.
+unsafe impl Send for IsolateHandle {}
+
+impl IsolateHandle {
+    pub(crate) fn new(isolate: V8IsolateHandle) -> Self {
+        Self {
+            isolate: isolate as usize,
+        }
+    }
+
+    fn raw(&self) -> V8IsolateHandle {
+        self.isolate as V8IsolateHandle
+    }
+
+    /// Request that any script currently running on this isolate stop at its
+    /// next safe point.
+    pub fn terminate_execution(&self) {
+        unsafe { ffi::shim_isolate_terminate_execution(self.raw()) };
+    }
+
+    /// Cancel a termination request that has not yet been observed by a
+    /// running script.
+    pub fn cancel_terminate_execution(&self) {
+        unsafe { ffi::shim_isolate_cancel_terminate_execution(self.raw()) };
+    }
+
+    /// Whether a termination request is currently unwinding JS execution on
+    /// this isolate.
+    pub fn is_execution_terminating(&self) -> bool {
+        unsafe { ffi::shim_isolate_is_execution_terminating(self.raw()) != 0 }
+    }
+}
+
+/// Run `body` under a wall-clock `timeout`.
+///
+/// A watchdog thread calls [`IsolateHandle::terminate_execution`] if `body`
+/// has not returned by the deadline; it is always joined before this
+/// returns, so a script that finishes quickly leaves no lingering thread
+/// behind. When `body` fails because the watchdog fired — detected via
+/// [`IsolateHandle::is_execution_terminating`] rather than by racing the
+/// deadline again — the underlying exception is replaced with a dedicated
+/// timeout error so callers can tell the two apart.
+pub(crate) fn run_with_timeout<T>(
+    handle: &IsolateHandle,
+    timeout: Duration,
+    body: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog_handle = handle.clone();
+    let watchdog = thread::spawn(move || {
+        if done_rx.recv_timeout(timeout).is_err() {
+            watchdog_handle.terminate_execution();
+        }
+    });
+
+    let result = body();
+    let _ = done_tx.send(());
+    let _ = watchdog.join();
+
+    // The watchdog may fire the instant the deadline passes, independent of
+    // whether `body` already had its result in hand — checked and cleared
+    // here regardless of `result`, or a termination request left pending
+    // would abort the *next* script run on this isolate instead.
+    if handle.is_execution_terminating() {
+        handle.cancel_terminate_execution();
+        return Err(V8Error::new(format!(
+            "execution terminated after {}ms",
+            timeout.as_millis()
+        )));
+    }
+
+    result
+}
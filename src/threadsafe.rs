@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::ptr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::{Result, V8Error};
+use crate::ffi::{self, V8ContextHandle, V8IsolateHandle};
+use crate::support::{take_error, take_value};
+use crate::value::JsValue;
+
+/// A cross-thread call into the owning isolate: encoded arguments plus a
+/// one-shot channel on which the drained result is returned.
+struct Task {
+    context: usize,
+    func_ref: u64,
+    args: Vec<u8>,
+    reply: Sender<Result<JsValue>>,
+}
+
+/// Per-isolate task queue. Background threads push [`Task`]s onto the sender;
+/// the JS thread drains them in [`pump`].
+struct Queue {
+    sender: Sender<Task>,
+    receiver: Mutex<Receiver<Task>>,
+}
+
+static QUEUES: OnceLock<Mutex<HashMap<usize, &'static Queue>>> = OnceLock::new();
+
+fn queues() -> &'static Mutex<HashMap<usize, &'static Queue>> {
+    QUEUES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Obtain (creating on first use) the task queue for `isolate`.
+fn queue_for(isolate: usize) -> &'static Queue {
+    let mut guard = queues().lock().unwrap();
+    guard.entry(isolate).or_insert_with(|| {
+        let (sender, receiver) = mpsc::channel();
+        // The queue lives for the process; leaking it keeps the sender valid for
+        // every `ThreadsafeFunction` clone regardless of isolate lifetime.
+        Box::leak(Box::new(Queue {
+            sender,
+            receiver: Mutex::new(receiver),
+        }))
+    })
+}
+
+/// A `Clone + Send` handle to a JS function that can be invoked from any Rust
+/// thread. Calls are marshaled onto the owning isolate's task queue and driven
+/// on the JS thread by [`crate::Context::pump`]; `call` blocks until the result
+/// is available, mirroring N-API's blocking ThreadsafeFunction mode.
+#[derive(Clone)]
+pub struct ThreadsafeFunction {
+    isolate: usize,
+    context: usize,
+    func_ref: u64,
+    sender: Sender<Task>,
+}
+
+// The raw handles are only ever dereferenced on the JS thread while draining the
+// queue; the handle itself just ferries identifiers across threads.
+unsafe impl Send for ThreadsafeFunction {}
+
+impl ThreadsafeFunction {
+    pub(crate) fn new(isolate: V8IsolateHandle, context: V8ContextHandle, func_ref: u64) -> Self {
+        let queue = queue_for(isolate as usize);
+        Self {
+            isolate: isolate as usize,
+            context: context as usize,
+            func_ref,
+            sender: queue.sender.clone(),
+        }
+    }
+
+    /// Enqueue a call onto the owning isolate and block until it completes.
+    ///
+    /// Returns an error if the isolate thread has stopped draining its queue
+    /// (for example because the [`crate::Context`] was dropped).
+    pub fn call(&self, args: &[JsValue]) -> Result<JsValue> {
+        let encoded = JsValue::Array(args.to_vec()).encode();
+        let (reply, result) = mpsc::channel();
+        let task = Task {
+            context: self.context,
+            func_ref: self.func_ref,
+            args: encoded,
+            reply,
+        };
+        self.sender
+            .send(task)
+            .map_err(|_| V8Error::new("owning isolate is no longer pumping its task queue"))?;
+        result
+            .recv()
+            .map_err(|_| V8Error::new("threadsafe call dropped before completion"))?
+    }
+
+    /// The isolate this handle is bound to, as an opaque address.
+    pub fn isolate(&self) -> usize {
+        self.isolate
+    }
+}
+
+/// Drain and execute every queued call for `isolate`. Runs on the JS thread.
+pub(crate) fn pump(isolate: V8IsolateHandle) {
+    let queue = queue_for(isolate as usize);
+    let receiver = queue.receiver.lock().unwrap();
+    while let Ok(task) = receiver.try_recv() {
+        let result = call_on_js_thread(task.context as V8ContextHandle, task.func_ref, &task.args);
+        // A disconnected caller simply means nobody is waiting; drop the result.
+        let _ = task.reply.send(result);
+    }
+}
+
+fn call_on_js_thread(context: V8ContextHandle, func_ref: u64, args: &[u8]) -> Result<JsValue> {
+    let mut result_ptr: *mut u8 = ptr::null_mut();
+    let mut result_len: usize = 0;
+    let mut error_ptr: *mut u8 = ptr::null_mut();
+    let mut error_len: usize = 0;
+
+    let args_ptr = if args.is_empty() {
+        ptr::null()
+    } else {
+        args.as_ptr()
+    };
+
+    let status = unsafe {
+        ffi::shim_context_call_js_function(
+            context,
+            func_ref,
+            args_ptr,
+            args.len(),
+            &mut result_ptr,
+            &mut result_len,
+            &mut error_ptr,
+            &mut error_len,
+        )
+    };
+
+    if status == 0 {
+        return Err(unsafe { take_error(error_ptr, error_len, "threadsafe JS call failed") });
+    }
+
+    unsafe { take_value(result_ptr, result_len) }
+}
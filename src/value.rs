@@ -1,32 +1,443 @@
-use std::fmt;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct JsValue {
-    raw: String,
-}
-
-impl JsValue {
-    pub(crate) fn new(raw: String) -> Self {
-        Self { raw }
-    }
-
-    pub fn as_str(&self) -> &str {
-        &self.raw
-    }
-
-    pub fn into_string(self) -> String {
-        self.raw
-    }
-}
-
-impl fmt::Display for JsValue {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.raw)
-    }
-}
-
-impl From<JsValue> for String {
-    fn from(value: JsValue) -> Self {
-        value.raw
-    }
-}
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::{Result, V8Error};
+
+/// A JavaScript value as it crosses the host boundary.
+///
+/// Values used to be flattened to a single `String`; every non-string shape
+/// (numbers, booleans, `null`, arrays, nested objects) was lost and non-UTF8
+/// payloads were silently mangled by `to_string_lossy`. The tagged form keeps
+/// the shape intact and round-trips across the C ABI as a length-prefixed
+/// encoded buffer (see [`JsValue::encode`]/[`JsValue::decode`]); the former
+/// string path is simply the [`JsValue::String`] variant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsValue>),
+    Object(BTreeMap<String, JsValue>),
+    Bytes(Vec<u8>),
+    /// A `Promise` that escaped as an eval/call result, carrying the opaque
+    /// ref id the shim assigned it when it persisted a `Global<Promise>` for
+    /// the value. Resolve it with [`crate::Context::resolve_promise`] rather
+    /// than reading it directly — the ref id has no meaning outside the
+    /// shim's promise table.
+    Promise(u64),
+}
+
+/// Tag bytes for the compact tag-length-value wire format.
+mod tag {
+    pub(super) const NULL: u8 = 0;
+    pub(super) const BOOL: u8 = 1;
+    pub(super) const NUMBER: u8 = 2;
+    pub(super) const STRING: u8 = 3;
+    pub(super) const ARRAY: u8 = 4;
+    pub(super) const OBJECT: u8 = 5;
+    pub(super) const BYTES: u8 = 6;
+    pub(super) const PROMISE: u8 = 7;
+}
+
+impl JsValue {
+    /// Construct a string value.
+    ///
+    /// Retained for callers that only ever dealt in strings; it is shorthand
+    /// for [`JsValue::String`].
+    pub(crate) fn new(raw: String) -> Self {
+        JsValue::String(raw)
+    }
+
+    /// Borrow the contents as a string slice when this is a [`JsValue::String`].
+    ///
+    /// Non-string variants have no textual backing and yield `""`; use
+    /// [`JsValue::as_string`] when the distinction matters.
+    pub fn as_str(&self) -> &str {
+        match self {
+            JsValue::String(value) => value,
+            _ => "",
+        }
+    }
+
+    /// Borrow the contents as a string slice, returning `None` for non-strings.
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            JsValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Consume the value, yielding the owned string for a [`JsValue::String`]
+    /// and `String::new()` otherwise.
+    pub fn into_string(self) -> String {
+        match self {
+            JsValue::String(value) => value,
+            _ => String::new(),
+        }
+    }
+
+    /// Serialize into the length-prefixed tag-length-value buffer used on the
+    /// FFI boundary.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.encode_into(&mut buffer);
+        buffer
+    }
+
+    fn encode_into(&self, buffer: &mut Vec<u8>) {
+        match self {
+            JsValue::Null => buffer.push(tag::NULL),
+            JsValue::Bool(value) => {
+                buffer.push(tag::BOOL);
+                buffer.push(u8::from(*value));
+            }
+            JsValue::Number(value) => {
+                buffer.push(tag::NUMBER);
+                buffer.extend_from_slice(&value.to_le_bytes());
+            }
+            JsValue::String(value) => {
+                buffer.push(tag::STRING);
+                encode_bytes(buffer, value.as_bytes());
+            }
+            JsValue::Array(values) => {
+                buffer.push(tag::ARRAY);
+                encode_len(buffer, values.len());
+                for value in values {
+                    value.encode_into(buffer);
+                }
+            }
+            JsValue::Object(entries) => {
+                buffer.push(tag::OBJECT);
+                encode_len(buffer, entries.len());
+                for (key, value) in entries {
+                    encode_bytes(buffer, key.as_bytes());
+                    value.encode_into(buffer);
+                }
+            }
+            JsValue::Bytes(value) => {
+                buffer.push(tag::BYTES);
+                encode_bytes(buffer, value);
+            }
+            JsValue::Promise(promise_ref) => {
+                buffer.push(tag::PROMISE);
+                buffer.extend_from_slice(&promise_ref.to_le_bytes());
+            }
+        }
+    }
+
+    /// Whether this value is a `Promise` ref rather than a settled value.
+    pub fn is_promise(&self) -> bool {
+        matches!(self, JsValue::Promise(_))
+    }
+
+    /// Extract the best-effort human-readable message from a rejection value,
+    /// used to build the [`V8Error`] a rejected promise surfaces as.
+    ///
+    /// A plain string rejection is used as-is; an `Error`-shaped object (or
+    /// anything else with a string `message` property) contributes that
+    /// property, since `Display`-formatting the whole object would otherwise
+    /// print its full `{:?}` dump instead of the message a caller actually
+    /// wants. Anything else falls back to `Display`.
+    pub(crate) fn rejection_message(&self) -> String {
+        match self {
+            JsValue::String(value) => value.clone(),
+            JsValue::Object(entries) => entries
+                .get("message")
+                .and_then(JsValue::as_string)
+                .map(str::to_string)
+                .unwrap_or_else(|| self.to_string()),
+            other => other.to_string(),
+        }
+    }
+
+    /// Decode a value from the tag-length-value buffer produced by the shim.
+    pub(crate) fn decode(buffer: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor::new(buffer);
+        let value = cursor.read_value()?;
+        if cursor.remaining() != 0 {
+            return Err(V8Error::new("trailing bytes in encoded JsValue buffer"));
+        }
+        Ok(value)
+    }
+
+    /// Build a `JsValue` from any `Serialize` type, going through
+    /// `serde_json::Value` as an intermediate representation.
+    ///
+    /// Lets a Rust `struct` cross into JS (via [`crate::Context::set_global_serde`]
+    /// or [`crate::Context::call_function_serde`]) without the caller manually
+    /// stringifying and re-parsing JSON on both sides.
+    pub fn from_serde<T: serde::Serialize>(value: &T) -> Result<Self> {
+        let json = serde_json::to_value(value)
+            .map_err(|err| V8Error::new(format!("failed to serialize value: {err}")))?;
+        Ok(Self::from_json(json))
+    }
+
+    /// Deserialize this value into any `DeserializeOwned` type, going through
+    /// `serde_json::Value` as an intermediate representation.
+    pub fn to_serde<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.to_json())
+            .map_err(|err| V8Error::new(format!("failed to deserialize value: {err}")))
+    }
+
+    fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => JsValue::Null,
+            serde_json::Value::Bool(value) => JsValue::Bool(value),
+            serde_json::Value::Number(number) => JsValue::Number(number.as_f64().unwrap_or(f64::NAN)),
+            serde_json::Value::String(value) => JsValue::String(value),
+            serde_json::Value::Array(values) => {
+                JsValue::Array(values.into_iter().map(JsValue::from_json).collect())
+            }
+            serde_json::Value::Object(entries) => JsValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, JsValue::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            JsValue::Null => serde_json::Value::Null,
+            JsValue::Bool(value) => serde_json::Value::Bool(*value),
+            JsValue::Number(value) => serde_json::Number::from_f64(*value)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            JsValue::String(value) => serde_json::Value::String(value.clone()),
+            JsValue::Array(values) => {
+                serde_json::Value::Array(values.iter().map(JsValue::to_json).collect())
+            }
+            JsValue::Object(entries) => serde_json::Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_json()))
+                    .collect(),
+            ),
+            // JSON has no byte-string type; round-trip through an array of
+            // byte values, matching how `serde_bytes`-less serde handles `Vec<u8>`.
+            JsValue::Bytes(bytes) => {
+                serde_json::Value::Array(bytes.iter().map(|byte| (*byte).into()).collect())
+            }
+            // A promise ref has no JSON shape; callers must settle it with
+            // `Context::resolve_promise` before converting to a typed value.
+            JsValue::Promise(_) => serde_json::Value::Null,
+        }
+    }
+}
+
+fn encode_len(buffer: &mut Vec<u8>, len: usize) {
+    buffer.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn encode_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    encode_len(buffer, bytes.len());
+    buffer.extend_from_slice(bytes);
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        if self.remaining() < count {
+            return Err(V8Error::new("truncated encoded JsValue buffer"));
+        }
+        let slice = &self.bytes[self.offset..self.offset + count];
+        self.offset += count;
+        Ok(slice)
+    }
+
+    fn read_tag(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.read_len()?;
+        self.take(len)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let bytes = self.read_bytes()?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| V8Error::new("encoded JsValue string was not valid UTF-8"))
+    }
+
+    fn read_value(&mut self) -> Result<JsValue> {
+        match self.read_tag()? {
+            tag::NULL => Ok(JsValue::Null),
+            tag::BOOL => Ok(JsValue::Bool(self.take(1)?[0] != 0)),
+            tag::NUMBER => {
+                let bytes = self.take(8)?;
+                let mut repr = [0u8; 8];
+                repr.copy_from_slice(bytes);
+                Ok(JsValue::Number(f64::from_le_bytes(repr)))
+            }
+            tag::STRING => Ok(JsValue::String(self.read_string()?)),
+            tag::ARRAY => {
+                let count = self.read_len()?;
+                let mut values = Vec::with_capacity(count);
+                for _ in 0..count {
+                    values.push(self.read_value()?);
+                }
+                Ok(JsValue::Array(values))
+            }
+            tag::OBJECT => {
+                let count = self.read_len()?;
+                let mut entries = BTreeMap::new();
+                for _ in 0..count {
+                    let key = self.read_string()?;
+                    entries.insert(key, self.read_value()?);
+                }
+                Ok(JsValue::Object(entries))
+            }
+            tag::BYTES => Ok(JsValue::Bytes(self.read_bytes()?.to_vec())),
+            tag::PROMISE => {
+                let bytes = self.take(8)?;
+                let mut repr = [0u8; 8];
+                repr.copy_from_slice(bytes);
+                Ok(JsValue::Promise(u64::from_le_bytes(repr)))
+            }
+            other => Err(V8Error::new(format!(
+                "unknown JsValue tag byte {other} in encoded buffer"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for JsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsValue::Null => f.write_str("null"),
+            JsValue::Bool(value) => write!(f, "{value}"),
+            JsValue::Number(value) => write!(f, "{value}"),
+            JsValue::String(value) => f.write_str(value),
+            JsValue::Array(_) | JsValue::Object(_) | JsValue::Bytes(_) | JsValue::Promise(_) => {
+                write!(f, "{self:?}")
+            }
+        }
+    }
+}
+
+impl From<JsValue> for String {
+    fn from(value: JsValue) -> Self {
+        value.into_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsValue;
+    use std::collections::BTreeMap;
+
+    fn round_trip(value: JsValue) {
+        let encoded = value.encode();
+        let decoded = JsValue::decode(&encoded).expect("value should decode");
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn round_trips_every_variant() {
+        round_trip(JsValue::Null);
+        round_trip(JsValue::Bool(true));
+        round_trip(JsValue::Bool(false));
+        round_trip(JsValue::Number(42.5));
+        round_trip(JsValue::String("hello, \u{1F600}".to_string()));
+        round_trip(JsValue::Array(vec![
+            JsValue::Number(1.0),
+            JsValue::String("two".to_string()),
+            JsValue::Null,
+        ]));
+        let mut object = BTreeMap::new();
+        object.insert("a".to_string(), JsValue::Number(1.0));
+        object.insert("b".to_string(), JsValue::Bool(false));
+        round_trip(JsValue::Object(object));
+        round_trip(JsValue::Bytes(vec![0, 1, 2, 255]));
+        round_trip(JsValue::Promise(7));
+    }
+
+    #[test]
+    fn nan_round_trips_as_nan() {
+        let encoded = JsValue::Number(f64::NAN).encode();
+        match JsValue::decode(&encoded).unwrap() {
+            JsValue::Number(value) => assert!(value.is_nan()),
+            other => panic!("expected a number, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decode_rejects_trailing_bytes() {
+        let mut encoded = JsValue::Null.encode();
+        encoded.push(0xFF);
+        assert!(JsValue::decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_truncated_buffer() {
+        let encoded = JsValue::String("hello".to_string()).encode();
+        assert!(JsValue::decode(&encoded[..encoded.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_unknown_tag() {
+        assert!(JsValue::decode(&[0xEE]).is_err());
+    }
+
+    #[test]
+    fn serde_round_trips_through_json() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Point {
+            x: f64,
+            y: f64,
+        }
+
+        let point = Point { x: 1.0, y: -2.5 };
+        let value = JsValue::from_serde(&point).expect("should serialize");
+        assert_eq!(
+            value,
+            JsValue::Object(BTreeMap::from([
+                ("x".to_string(), JsValue::Number(1.0)),
+                ("y".to_string(), JsValue::Number(-2.5)),
+            ]))
+        );
+        let back: Point = value.to_serde().expect("should deserialize");
+        assert_eq!(back, point);
+    }
+
+    #[test]
+    fn rejection_message_prefers_object_message_field() {
+        let mut object = BTreeMap::new();
+        object.insert("name".to_string(), JsValue::String("Error".to_string()));
+        object.insert("message".to_string(), JsValue::String("boom".to_string()));
+        assert_eq!(JsValue::Object(object).rejection_message(), "boom");
+    }
+
+    #[test]
+    fn rejection_message_uses_plain_string_directly() {
+        assert_eq!(
+            JsValue::String("just a string".to_string()).rejection_message(),
+            "just a string"
+        );
+    }
+
+    #[test]
+    fn rejection_message_falls_back_to_display_for_anything_else() {
+        assert_eq!(JsValue::Number(3.0).rejection_message(), "3");
+    }
+}